@@ -0,0 +1,83 @@
+//! Wilson score confidence bounds for guess accuracy.
+//!
+//! Raw accuracy treats 1/1 and 60/100 alike. The Wilson lower bound asks how
+//! low the true accuracy could plausibly be given the sample size, so small
+//! lucky streaks no longer clear the humanity rule.
+
+use alloy_primitives::U256;
+
+use crate::math::{self, mul_div, mul_wad, Rounding, BPS, WAD};
+
+/// Lower bound of the Wilson score interval for `correct / total`, in basis
+/// points, for a normal quantile `z_wad` (e.g. 1.96e18 for 95%).
+///
+/// Computed as `(c + z²/2 − z·√(c(n−c)/n + z²/4)) / (n + z²)`, which is the
+/// textbook form multiplied through by `n`. Returns `None` on inconsistent
+/// input (`correct > total`, `total == 0`) or overflow.
+pub fn wilson_lower_bound_bps(correct: U256, total: U256, z_wad: U256) -> Option<U256> {
+    if total == U256::ZERO || correct > total {
+        return None;
+    }
+
+    let z2 = mul_wad(z_wad, z_wad, Rounding::Down)?;
+    let c = correct.checked_mul(WAD)?;
+    let n = total.checked_mul(WAD)?;
+
+    // c(n−c)/n in WAD, then √(· + z²/4).
+    let spread = mul_div(
+        correct.checked_mul(total - correct)?,
+        WAD,
+        total,
+        Rounding::Down,
+    )?;
+    let root = math::sqrt_wad(spread.checked_add(z2 / U256::from(4))?);
+    let margin = mul_wad(z_wad, root, Rounding::Down)?;
+
+    let centre = c.checked_add(z2 / U256::from(2))?;
+    let numerator = centre.saturating_sub(margin);
+    let denominator = n.checked_add(z2)?;
+
+    mul_div(numerator, U256::from(BPS), denominator, Rounding::Down)
+}
+
+/// Plain `numerator / denominator` in bps, rounded down; zero for an empty
+/// denominator.
+pub fn ratio_bps(numerator: U256, denominator: U256) -> U256 {
+    mul_div(numerator, U256::from(BPS), denominator, Rounding::Down).unwrap_or_default()
+}
+
+/// Beta-posterior summary for `successes` out of `trials` under a
+/// `Beta(alpha, beta)` prior (both WAD). Returns `(mean_bps, lower_bps)`,
+/// where the lower bound is `mean − z·sd` of the posterior, floored at zero.
+///
+/// The normal approximation keeps this to one square root; it is tight
+/// enough to rank agents once they have a few dozen encounters, and the
+/// prior keeps the first few from producing extreme scores.
+pub fn beta_posterior_bps(
+    successes: U256,
+    trials: U256,
+    alpha_wad: U256,
+    beta_wad: U256,
+    z_wad: U256,
+) -> Option<(U256, U256)> {
+    if successes > trials {
+        return None;
+    }
+
+    let a = alpha_wad.checked_add(successes.checked_mul(WAD)?)?;
+    let b = beta_wad.checked_add((trials - successes).checked_mul(WAD)?)?;
+    let s = a.checked_add(b)?;
+    if s == U256::ZERO {
+        return None;
+    }
+
+    // mean = a/s; var = (a/s)(b/s)/(s+1), all in WAD.
+    let mean = math::div_wad(a, s, Rounding::Down)?;
+    let complement = math::div_wad(b, s, Rounding::Down)?;
+    let variance = mul_div(mean, complement, s.checked_add(WAD)?, Rounding::Down)?;
+    let sd = math::sqrt_wad(variance);
+    let lower = mean.saturating_sub(mul_wad(z_wad, sd, Rounding::Down)?);
+
+    let to_bps = |x: U256| mul_div(x, U256::from(BPS), WAD, Rounding::Down);
+    Some((to_bps(mean)?, to_bps(lower)?))
+}
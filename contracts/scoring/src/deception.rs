@@ -0,0 +1,53 @@
+//! Deception ratings for AI agents: how often a bot passed as human.
+//!
+//! The raw rate treats 1/1 and 60/100 alike, so rankings use a Beta
+//! posterior instead (see `confidence::beta_posterior_bps`): small samples
+//! are pulled toward the prior and ranked by a conservative lower bound.
+
+use alloy_primitives::U256;
+
+use crate::confidence;
+use crate::math::{mul_div, Rounding, WAD};
+
+/// Uniform `Beta(1, 1)` prior.
+pub const DEFAULT_PRIOR_ALPHA_WAD: U256 = WAD;
+pub const DEFAULT_PRIOR_BETA_WAD: U256 = WAD;
+/// 1.96: lower end of a 95% credible interval.
+pub const DEFAULT_CREDIBLE_Z_WAD: u64 = 1_960_000_000_000_000_000;
+
+/// Raw share of encounters in which the bot fooled the human, in bps.
+pub fn rating_bps(times_fooled_human: U256, total_interactions: U256) -> U256 {
+    confidence::ratio_bps(times_fooled_human, total_interactions)
+}
+
+/// Raw share of encounters in which the bot fooled the human, in whole
+/// percent rounded down: the unit of `calculateDeceptionRating` in the
+/// Solidity contracts. Zero when there were no encounters.
+pub fn rating_pct(times_fooled_human: U256, total_interactions: U256) -> U256 {
+    mul_div(
+        times_fooled_human,
+        U256::from(100),
+        total_interactions,
+        Rounding::Down,
+    )
+    .unwrap_or_default()
+}
+
+/// `(meanBps, lowerBoundBps)` of the posterior under a `Beta(alpha, beta)`
+/// prior (WAD) and credible `z` (WAD); zeros for inconsistent input.
+pub fn bayesian_rating_bps(
+    times_fooled_human: U256,
+    total_interactions: U256,
+    alpha_wad: U256,
+    beta_wad: U256,
+    z_wad: U256,
+) -> (U256, U256) {
+    confidence::beta_posterior_bps(
+        times_fooled_human,
+        total_interactions,
+        alpha_wad,
+        beta_wad,
+        z_wad,
+    )
+    .unwrap_or((U256::ZERO, U256::ZERO))
+}
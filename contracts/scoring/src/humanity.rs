@@ -0,0 +1,180 @@
+//! The humanity rule: does a player's record look like a human's?
+//!
+//! A player passes when their accuracy beats the threshold, their average
+//! response time sits inside the human band, the sample is big enough for
+//! the accuracy to be significant (see `confidence`), and, when individual
+//! latencies are available, their timing is not machine-regular.
+//! Thresholds are parameters, so every consumer can apply the contract's
+//! live policy; the `DEFAULT_*` constants are what it is deployed with.
+
+use alloy_primitives::U256;
+
+use crate::confidence;
+use crate::stats::LatencyStats;
+
+pub const DEFAULT_MIN_ACCURACY_PCT: u64 = 60;
+pub const DEFAULT_MIN_LATENCY_MS: u64 = 500;
+pub const DEFAULT_MAX_LATENCY_MS: u64 = 240_000;
+/// Human timing varies by well over 15% between matches.
+pub const DEFAULT_MIN_LATENCY_CV_BPS: u64 = 1_500;
+/// No single vote should land faster than 300 ms after the prompt.
+pub const DEFAULT_MIN_SINGLE_LATENCY_MS: u64 = 300;
+
+/// 95% two-sided normal quantile, 1.96, in WAD.
+pub const DEFAULT_WILSON_Z_WAD: u64 = 1_960_000_000_000_000_000;
+/// Fewer matches than this say nothing about a player.
+pub const DEFAULT_MIN_MATCHES: u64 = 5;
+/// The accuracy lower bound must beat a coin flip.
+pub const DEFAULT_MIN_ACCURACY_LOWER_BOUND_BPS: u64 = 5_000;
+
+/// Outcome of the humanity rule, with the first check that failed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Human,
+    /// More correct guesses than matches, or numbers too large to be real.
+    Inconsistent {
+        correct: U256,
+        total: U256,
+    },
+    InsufficientMatches {
+        total: U256,
+        required: U256,
+    },
+    AccuracyTooLow {
+        accuracy_pct: U256,
+        required: U256,
+    },
+    TooFast {
+        avg_ms: U256,
+        min_ms: U256,
+    },
+    TooSlow {
+        avg_ms: U256,
+        max_ms: U256,
+    },
+    /// Accuracy clears the bar, but not with enough confidence.
+    LowConfidence {
+        lower_bound_bps: U256,
+        required: U256,
+    },
+}
+
+impl Verdict {
+    /// Stable reason code: 0 human, then one code per failure in declaration
+    /// order. Codes are never reused.
+    pub fn code(&self) -> u8 {
+        match self {
+            Verdict::Human => 0,
+            Verdict::Inconsistent { .. } => 1,
+            Verdict::InsufficientMatches { .. } => 2,
+            Verdict::AccuracyTooLow { .. } => 3,
+            Verdict::TooFast { .. } => 4,
+            Verdict::TooSlow { .. } => 5,
+            Verdict::LowConfidence { .. } => 6,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        *self == Verdict::Human
+    }
+}
+
+/// Below this many samples the spread is not judged.
+pub const MIN_SAMPLES_FOR_CV: usize = 5;
+
+/// Thresholds of the humanity rule (see `HumanityPolicy` in the contract).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HumanityRule {
+    pub min_accuracy_pct: U256,
+    pub min_latency_ms: U256,
+    pub max_latency_ms: U256,
+    pub wilson_z_wad: U256,
+    pub min_matches: U256,
+    pub min_accuracy_lower_bound_bps: U256,
+}
+
+impl HumanityRule {
+    /// Applies the rule to a player's totals. Checks run in a fixed order
+    /// and the first failure is reported.
+    pub fn verdict(&self, correct: U256, total: U256, avg_response_time_ms: U256) -> Verdict {
+        let inconsistent = Verdict::Inconsistent { correct, total };
+        if correct > total {
+            return inconsistent;
+        }
+        let min_matches = self.min_matches;
+        if total == U256::ZERO || total < min_matches {
+            return Verdict::InsufficientMatches {
+                total,
+                required: min_matches.max(U256::from(1)),
+            };
+        }
+        // Only absurd totals can overflow here; refuse them rather than wrap.
+        let Some(scaled) = correct.checked_mul(U256::from(100)) else {
+            return inconsistent;
+        };
+        let accuracy_pct = scaled / total;
+        let min_accuracy_pct = self.min_accuracy_pct;
+        if accuracy_pct <= min_accuracy_pct {
+            return Verdict::AccuracyTooLow {
+                accuracy_pct,
+                required: min_accuracy_pct,
+            };
+        }
+        let min_ms = self.min_latency_ms;
+        if avg_response_time_ms <= min_ms {
+            return Verdict::TooFast {
+                avg_ms: avg_response_time_ms,
+                min_ms,
+            };
+        }
+        let max_ms = self.max_latency_ms;
+        if avg_response_time_ms >= max_ms {
+            return Verdict::TooSlow {
+                avg_ms: avg_response_time_ms,
+                max_ms,
+            };
+        }
+        let required = self.min_accuracy_lower_bound_bps;
+        let Some(lower_bound_bps) =
+            confidence::wilson_lower_bound_bps(correct, total, self.wilson_z_wad)
+        else {
+            return inconsistent;
+        };
+        if lower_bound_bps < required {
+            return Verdict::LowConfidence {
+                lower_bound_bps,
+                required,
+            };
+        }
+        Verdict::Human
+    }
+}
+
+impl Default for HumanityRule {
+    fn default() -> Self {
+        Self {
+            min_accuracy_pct: U256::from(DEFAULT_MIN_ACCURACY_PCT),
+            min_latency_ms: U256::from(DEFAULT_MIN_LATENCY_MS),
+            max_latency_ms: U256::from(DEFAULT_MAX_LATENCY_MS),
+            wilson_z_wad: U256::from(DEFAULT_WILSON_Z_WAD),
+            min_matches: U256::from(DEFAULT_MIN_MATCHES),
+            min_accuracy_lower_bound_bps: U256::from(DEFAULT_MIN_ACCURACY_LOWER_BOUND_BPS),
+        }
+    }
+}
+
+/// Spread and floor checks over individual latencies: no response faster
+/// than `min_single_latency_ms`, and a coefficient of variation of at least
+/// `min_latency_cv_bps`. The CV test needs `MIN_SAMPLES_FOR_CV` samples,
+/// since a handful of votes can look regular by chance.
+pub fn distribution_ok(
+    stats: &LatencyStats,
+    samples: usize,
+    min_latency_cv_bps: U256,
+    min_single_latency_ms: U256,
+) -> bool {
+    if stats.min < min_single_latency_ms {
+        return false;
+    }
+    samples < MIN_SAMPLES_FOR_CV || stats.cv_bps >= min_latency_cv_bps
+}
@@ -0,0 +1,22 @@
+//! Scoring rules for the Detective game.
+//!
+//! The single source of truth for how players and bots are judged: the
+//! humanity rule, the confidence bounds behind it, latency statistics and
+//! deception ratings. The Stylus verifier links this crate, and other
+//! consumers (CLIs, wasm builds for the frontend) should too rather than
+//! re-implementing the rules.
+//!
+//! Everything public here is stable API under semver: a rule that changes
+//! outcomes for existing inputs is a breaking change. `vectors.json` holds
+//! reference inputs and outputs that any port or binding must reproduce.
+//!
+//! `no_std` (with `alloc`); all arithmetic is on `U256` with checked or
+//! 512-bit intermediates, so results are identical on every target.
+#![no_std]
+extern crate alloc;
+
+pub mod confidence;
+pub mod deception;
+pub mod humanity;
+pub mod math;
+pub mod stats;
@@ -0,0 +1,500 @@
+//! 18-decimal fixed-point math over `U256` / `I256`.
+//!
+//! A WAD value `x` represents `x / 1e18`. Every function is overflow-checked
+//! and returns `None` rather than wrapping or panicking, so callers can turn
+//! bad input into a typed revert. `ln` and `exp` reduce their argument by
+//! powers of two and finish with a short series, which keeps them within a
+//! few units of 1e-17 relative error across the whole domain.
+
+use alloy_primitives::{Uint, I256, U256, U512};
+
+/// 1.0 in WAD.
+pub const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+/// 100% in basis points.
+pub const BPS: u64 = 10_000;
+/// ln(2) in WAD.
+pub const LN2_WAD: U256 = U256::from_limbs([693_147_180_559_945_309, 0, 0, 0]);
+
+/// Inputs below this make `exp` round to zero in WAD (≈ -41.45).
+const EXP_MIN_WAD: i128 = -41_446_531_673_892_822_313;
+/// Inputs above this overflow `exp` in WAD (the exact limit, ≈ 135.999, is
+/// enforced when scaling by `2^k`).
+const EXP_MAX_WAD: i128 = 136_000_000_000_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To nearest, ties away from zero.
+    Nearest,
+}
+
+/// `x * y / denominator` with a 512-bit intermediate, so only the final
+/// result has to fit in 256 bits.
+pub fn mul_div(x: U256, y: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    if denominator == U256::ZERO {
+        return None;
+    }
+
+    let product: U512 = x.widening_mul(y);
+    let d = U512::from_limbs_slice(denominator.as_limbs());
+    let (mut quotient, remainder) = product.div_rem(d);
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder != U512::ZERO,
+        Rounding::Nearest => remainder >= d - remainder,
+    };
+    if round_up {
+        quotient = quotient.checked_add(U512::from(1))?;
+    }
+
+    U256::checked_from_limbs_slice(quotient.as_limbs())
+}
+
+/// `x * y` for two WAD values.
+pub fn mul_wad(x: U256, y: U256, rounding: Rounding) -> Option<U256> {
+    mul_div(x, y, WAD, rounding)
+}
+
+/// `x / y` for two WAD values.
+pub fn div_wad(x: U256, y: U256, rounding: Rounding) -> Option<U256> {
+    mul_div(x, WAD, y, rounding)
+}
+
+/// Signed `x * y` for two WAD values, truncating toward zero.
+pub fn mul_wad_signed(x: I256, y: I256) -> Option<I256> {
+    let (sx, ax) = x.into_sign_and_abs();
+    let (sy, ay) = y.into_sign_and_abs();
+    let magnitude = mul_wad(ax, ay, Rounding::Down)?;
+    I256::checked_from_sign_and_abs(sx * sy, magnitude)
+}
+
+/// Signed `x / y` for two WAD values, truncating toward zero.
+pub fn div_wad_signed(x: I256, y: I256) -> Option<I256> {
+    let (sx, ax) = x.into_sign_and_abs();
+    let (sy, ay) = y.into_sign_and_abs();
+    let magnitude = div_wad(ax, ay, Rounding::Down)?;
+    I256::checked_from_sign_and_abs(sx * sy, magnitude)
+}
+
+/// Square root of a WAD value, rounded down.
+pub fn sqrt_wad(x: U256) -> U256 {
+    // sqrt(x / 1e18) * 1e18 == sqrt(x * 1e18); the product may need 512 bits.
+    let product: U512 = x.widening_mul(WAD);
+    let root = isqrt(product);
+    // sqrt of a 512-bit value always fits in 256 bits.
+    U256::from_limbs_slice(&root.as_limbs()[..4])
+}
+
+/// Integer square root by Newton's method, rounded down. Unlike ruint's
+/// `root`, this needs no floating point and so works without `std`.
+pub(crate) fn isqrt<const BITS: usize, const LIMBS: usize>(
+    n: Uint<BITS, LIMBS>,
+) -> Uint<BITS, LIMBS> {
+    if n == Uint::ZERO {
+        return n;
+    }
+    // Start above the root so the iteration decreases monotonically.
+    let mut x = Uint::<BITS, LIMBS>::from(1) << n.bit_len().div_ceil(2);
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Natural logarithm of a positive WAD value.
+pub fn ln_wad(x: U256) -> Option<I256> {
+    if x == U256::ZERO {
+        return None;
+    }
+
+    // Normalise to y in [1, 2) so that x = y * 2^k.
+    let mut k = x.bit_len() as i64 - WAD.bit_len() as i64;
+    let mut y = if k >= 0 {
+        x >> k as usize
+    } else {
+        x << (-k) as usize
+    };
+    if y >= WAD << 1 {
+        y >>= 1;
+        k += 1;
+    } else if y < WAD {
+        y <<= 1;
+        k -= 1;
+    }
+
+    // ln(y) = 2·atanh(z) = 2·(z + z³/3 + z⁵/5 + …), z = (y−1)/(y+1) < 1/3.
+    let z = mul_div(y - WAD, WAD, y + WAD, Rounding::Down)?;
+    let z2 = mul_wad(z, z, Rounding::Down)?;
+    let mut term = z;
+    let mut series = U256::ZERO;
+    let mut n = 1u64;
+    while term != U256::ZERO {
+        series += term / U256::from(n);
+        term = mul_wad(term, z2, Rounding::Down)?;
+        n += 2;
+    }
+
+    let ln_y = I256::try_from(series << 1).ok()?;
+    let k_ln2 = I256::try_from(LN2_WAD)
+        .ok()?
+        .checked_mul(I256::try_from(k).ok()?)?;
+    k_ln2.checked_add(ln_y)
+}
+
+/// `e^x` for a signed WAD exponent. Rounds to zero below about -41.4 and
+/// returns `None` above about 136, where the result leaves `U256`.
+pub fn exp_wad(x: I256) -> Option<U256> {
+    if x < I256::try_from(EXP_MIN_WAD).ok()? {
+        return Some(U256::ZERO);
+    }
+    if x > I256::try_from(EXP_MAX_WAD).ok()? {
+        return None;
+    }
+
+    // x = k·ln2 + r with |r| <= ln2/2, so e^x = 2^k · e^r.
+    let ln2 = I256::try_from(LN2_WAD).ok()?;
+    let half = ln2 / I256::try_from(2).ok()?;
+    let k = if x.is_negative() {
+        (x - half) / ln2
+    } else {
+        (x + half) / ln2
+    };
+    let r = x - k * ln2;
+
+    // Taylor series for e^r; |r| < 0.35 so ~15 terms reach WAD precision.
+    let wad = I256::try_from(WAD).ok()?;
+    let mut term = wad;
+    let mut sum = wad;
+    let mut i = 1i64;
+    while !term.is_zero() {
+        term = mul_wad_signed(term, r)? / I256::try_from(i).ok()?;
+        sum += term;
+        i += 1;
+    }
+
+    let e_r = sum.into_raw();
+    let k = k.as_i64();
+    if k >= 0 {
+        let shift = k as usize;
+        if e_r.leading_zeros() < shift {
+            return None;
+        }
+        Some(e_r << shift)
+    } else {
+        Some(e_r >> (-k) as usize)
+    }
+}
+
+/// `x^y` for a positive WAD base and signed WAD exponent, as `e^(y·ln x)`.
+/// `0^y` is `1` for `y == 0`, `0` for `y > 0` and undefined otherwise.
+pub fn pow_wad(x: U256, y: I256) -> Option<U256> {
+    if x == U256::ZERO {
+        return if y.is_zero() {
+            Some(WAD)
+        } else if y.is_positive() {
+            Some(U256::ZERO)
+        } else {
+            None
+        };
+    }
+    if x == WAD || y.is_zero() {
+        return Some(WAD);
+    }
+    exp_wad(mul_wad_signed(ln_wad(x)?, y)?)
+}
+
+/// `x^n` for a WAD base and integer exponent, by repeated squaring. Exact up
+/// to the rounding of each WAD multiplication.
+pub fn pow_int_wad(x: U256, mut n: u32) -> Option<U256> {
+    let mut base = x;
+    let mut result = WAD;
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mul_wad(result, base, Rounding::Down)?;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = mul_wad(base, base, Rounding::Down)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: U256 = U256::MAX;
+
+    fn wad(x: u128) -> U256 {
+        U256::from(x)
+    }
+
+    fn iwad(x: i128) -> I256 {
+        I256::try_from(x).unwrap()
+    }
+
+    /// Within 1e-16 relative error, or 100 wei near zero.
+    fn assert_close(actual: U256, expected: U256) {
+        let err = if actual > expected {
+            actual - expected
+        } else {
+            expected - actual
+        };
+        let tolerance = (expected / U256::from(10u64.pow(16))).max(U256::from(100));
+        assert!(
+            err <= tolerance,
+            "got {actual}, expected {expected} (off by {err})"
+        );
+    }
+
+    fn assert_close_signed(actual: I256, expected: I256) {
+        assert_eq!(
+            actual.sign(),
+            expected.sign(),
+            "got {actual}, expected {expected}"
+        );
+        assert_close(actual.unsigned_abs(), expected.unsigned_abs());
+    }
+
+    #[test]
+    fn mul_div_rounds_each_way() {
+        let (seven, two) = (U256::from(7), U256::from(2));
+        let one = U256::from(1);
+        assert_eq!(
+            mul_div(seven, one, two, Rounding::Down),
+            Some(U256::from(3))
+        );
+        assert_eq!(mul_div(seven, one, two, Rounding::Up), Some(U256::from(4)));
+        assert_eq!(
+            mul_div(seven, one, two, Rounding::Nearest),
+            Some(U256::from(4))
+        );
+
+        let three = U256::from(3);
+        assert_eq!(
+            mul_div(U256::from(4), one, three, Rounding::Nearest),
+            Some(one)
+        );
+        assert_eq!(
+            mul_div(U256::from(5), one, three, Rounding::Nearest),
+            Some(two)
+        );
+        assert_eq!(mul_div(U256::from(6), one, three, Rounding::Up), Some(two));
+    }
+
+    #[test]
+    fn mul_div_uses_a_wide_intermediate() {
+        assert_eq!(mul_div(MAX, MAX, MAX, Rounding::Down), Some(MAX));
+        assert_eq!(mul_div(MAX, MAX, MAX, Rounding::Up), Some(MAX));
+        assert_eq!(
+            mul_div(MAX, U256::from(2), U256::from(4), Rounding::Down),
+            Some(MAX >> 1)
+        );
+    }
+
+    #[test]
+    fn mul_div_rejects_zero_denominator_and_overflow() {
+        assert_eq!(
+            mul_div(U256::from(1), U256::from(1), U256::ZERO, Rounding::Down),
+            None
+        );
+        assert_eq!(
+            mul_div(MAX, U256::from(2), U256::from(1), Rounding::Down),
+            None
+        );
+        assert_eq!(mul_div(MAX, MAX, MAX - U256::from(1), Rounding::Down), None);
+        // (2^192 - 1)(2^192 + 1) / 2^128 = MAX rounded down; rounding up overflows.
+        let one = U256::from(1);
+        let (x, y, d) = ((one << 192) - one, (one << 192) + one, one << 128);
+        assert_eq!(mul_div(x, y, d, Rounding::Down), Some(MAX));
+        assert_eq!(mul_div(x, y, d, Rounding::Up), None);
+        assert_eq!(mul_div(x, y, d, Rounding::Nearest), None);
+    }
+
+    #[test]
+    fn wad_helpers() {
+        let half = wad(500_000_000_000_000_000);
+        assert_eq!(
+            mul_wad(half, half, Rounding::Down),
+            Some(wad(250_000_000_000_000_000))
+        );
+        assert_eq!(
+            div_wad(WAD, wad(3_000_000_000_000_000_000), Rounding::Down),
+            Some(wad(333_333_333_333_333_333))
+        );
+        assert_eq!(
+            div_wad(WAD, wad(3_000_000_000_000_000_000), Rounding::Up),
+            Some(wad(333_333_333_333_333_334))
+        );
+        assert_eq!(div_wad(WAD, U256::ZERO, Rounding::Down), None);
+        assert_eq!(
+            mul_wad_signed(
+                iwad(-1_500_000_000_000_000_000),
+                iwad(2_000_000_000_000_000_000)
+            ),
+            Some(iwad(-3_000_000_000_000_000_000))
+        );
+        assert_eq!(
+            div_wad_signed(
+                iwad(-1_000_000_000_000_000_000),
+                iwad(-4_000_000_000_000_000_000)
+            ),
+            Some(iwad(250_000_000_000_000_000))
+        );
+    }
+
+    #[test]
+    fn sqrt_wad_matches_reference() {
+        assert_eq!(sqrt_wad(U256::ZERO), U256::ZERO);
+        assert_eq!(sqrt_wad(WAD), WAD);
+        assert_eq!(
+            sqrt_wad(wad(4_000_000_000_000_000_000)),
+            wad(2_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            sqrt_wad(wad(2_000_000_000_000_000_000)),
+            wad(1_414_213_562_373_095_048)
+        );
+        // 1e-18 -> 1e-9.
+        assert_eq!(sqrt_wad(U256::from(1)), U256::from(1_000_000_000));
+        // Exact square root of MAX·1e18, rounded down.
+        let root = sqrt_wad(MAX);
+        let product: U512 = MAX.widening_mul(WAD);
+        let r = U512::from_limbs_slice(root.as_limbs());
+        assert!(r * r <= product && (r + U512::from(1)) * (r + U512::from(1)) > product);
+    }
+
+    #[test]
+    fn ln_wad_matches_reference() {
+        assert_eq!(ln_wad(U256::ZERO), None);
+        assert_eq!(ln_wad(WAD), Some(I256::ZERO));
+        assert_close_signed(
+            ln_wad(wad(2_000_000_000_000_000_000)).unwrap(),
+            iwad(693_147_180_559_945_309),
+        );
+        assert_close_signed(
+            ln_wad(wad(500_000_000_000_000_000)).unwrap(),
+            iwad(-693_147_180_559_945_309),
+        );
+        assert_close_signed(
+            ln_wad(wad(2_718_281_828_459_045_235)).unwrap(),
+            iwad(1_000_000_000_000_000_000),
+        );
+        assert_close_signed(
+            ln_wad(wad(10_000_000_000_000_000_000)).unwrap(),
+            iwad(2_302_585_092_994_045_684),
+        );
+        // Smallest and largest representable inputs.
+        assert_close_signed(
+            ln_wad(U256::from(1)).unwrap(),
+            iwad(-41_446_531_673_892_822_313),
+        );
+        assert_close_signed(ln_wad(MAX).unwrap(), iwad(135_999_146_549_453_176_898));
+    }
+
+    #[test]
+    fn exp_wad_matches_reference() {
+        assert_eq!(exp_wad(I256::ZERO), Some(WAD));
+        assert_close(
+            exp_wad(iwad(1_000_000_000_000_000_000)).unwrap(),
+            wad(2_718_281_828_459_045_235),
+        );
+        assert_close(
+            exp_wad(iwad(-1_000_000_000_000_000_000)).unwrap(),
+            wad(367_879_441_171_442_321),
+        );
+        assert_close(
+            exp_wad(iwad(10_000_000_000_000_000_000)).unwrap(),
+            wad(22_026_465_794_806_716_516_957),
+        );
+        assert_close(
+            exp_wad(iwad(693_147_180_559_945_309)).unwrap(),
+            wad(2_000_000_000_000_000_000),
+        );
+    }
+
+    #[test]
+    fn exp_wad_boundaries() {
+        // e^EXP_MIN_WAD is about 1 wei; anything below rounds to zero.
+        assert!(exp_wad(iwad(EXP_MIN_WAD)).unwrap() <= U256::from(1));
+        assert_eq!(exp_wad(iwad(EXP_MIN_WAD - 1)), Some(U256::ZERO));
+        assert_eq!(
+            exp_wad(iwad(-100_000_000_000_000_000_000)),
+            Some(U256::ZERO)
+        );
+        // e^136 exceeds U256 even though it passes the coarse range check.
+        assert_eq!(exp_wad(iwad(EXP_MAX_WAD)), None);
+        assert_eq!(exp_wad(iwad(EXP_MAX_WAD + 1)), None);
+        // Just under ln(MAX / 1e18) still fits.
+        let near_max = exp_wad(iwad(135_999_000_000_000_000_000)).unwrap();
+        assert!(near_max > MAX >> 1);
+    }
+
+    #[test]
+    fn pow_wad_matches_reference() {
+        let half = iwad(500_000_000_000_000_000);
+        assert_close(
+            pow_wad(wad(2_000_000_000_000_000_000), half).unwrap(),
+            wad(1_414_213_562_373_095_048),
+        );
+        assert_close(
+            pow_wad(wad(4_000_000_000_000_000_000), half).unwrap(),
+            wad(2_000_000_000_000_000_000),
+        );
+        assert_close(
+            pow_wad(
+                wad(3_000_000_000_000_000_000),
+                iwad(1_500_000_000_000_000_000),
+            )
+            .unwrap(),
+            wad(5_196_152_422_706_631_880),
+        );
+        assert_close(
+            pow_wad(
+                wad(2_000_000_000_000_000_000),
+                iwad(10_000_000_000_000_000_000),
+            )
+            .unwrap(),
+            wad(1_024_000_000_000_000_000_000),
+        );
+        assert_close(
+            pow_wad(
+                wad(2_000_000_000_000_000_000),
+                iwad(-1_000_000_000_000_000_000),
+            )
+            .unwrap(),
+            wad(500_000_000_000_000_000),
+        );
+        assert_eq!(pow_wad(WAD, iwad(-7_000_000_000_000_000_000)), Some(WAD));
+        assert_eq!(pow_wad(wad(5), I256::ZERO), Some(WAD));
+    }
+
+    #[test]
+    fn pow_wad_zero_base() {
+        assert_eq!(pow_wad(U256::ZERO, I256::ZERO), Some(WAD));
+        assert_eq!(pow_wad(U256::ZERO, iwad(1)), Some(U256::ZERO));
+        assert_eq!(pow_wad(U256::ZERO, iwad(-1)), None);
+    }
+
+    #[test]
+    fn pow_int_wad_is_exact_for_exact_products() {
+        assert_eq!(
+            pow_int_wad(wad(2_000_000_000_000_000_000), 10),
+            Some(wad(1_024_000_000_000_000_000_000))
+        );
+        assert_eq!(
+            pow_int_wad(wad(500_000_000_000_000_000), 3),
+            Some(wad(125_000_000_000_000_000))
+        );
+        assert_eq!(pow_int_wad(wad(7), 0), Some(WAD));
+        assert_eq!(pow_int_wad(MAX, 2), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Summary statistics over per-match response latencies.
+//!
+//! A script can hold its *average* inside the human band while answering
+//! with machine-regular timing; the spread of the samples gives it away.
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+use crate::math::{self, BPS};
+
+/// Upper bound on samples per call, keeping the sort and loops cheap.
+pub const MAX_LATENCY_SAMPLES: usize = 256;
+
+pub struct LatencyStats {
+    pub mean: U256,
+    /// Population variance, in ms².
+    pub variance: U256,
+    /// Coefficient of variation (stddev / mean) in basis points.
+    pub cv_bps: U256,
+    pub median: U256,
+    pub min: U256,
+}
+
+/// Returns `None` for an empty slice or if any intermediate overflows.
+pub fn latency_stats(samples: &[U256]) -> Option<LatencyStats> {
+    if samples.is_empty() {
+        return None;
+    }
+    let n = U256::from(samples.len());
+
+    let sum = samples
+        .iter()
+        .try_fold(U256::ZERO, |acc, x| acc.checked_add(*x))?;
+    let mean = sum / n;
+
+    let squared_deviations = samples.iter().try_fold(U256::ZERO, |acc, x| {
+        let d = if *x > mean { *x - mean } else { mean - *x };
+        acc.checked_add(d.checked_mul(d)?)
+    })?;
+    let variance = squared_deviations / n;
+
+    let cv_bps = if mean == U256::ZERO {
+        U256::ZERO
+    } else {
+        math::isqrt(variance).checked_mul(U256::from(BPS))? / mean
+    };
+
+    let mut sorted: Vec<U256> = samples.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        // Halve before adding so the sum cannot overflow.
+        (sorted[mid - 1] >> 1)
+            + (sorted[mid] >> 1)
+            + (sorted[mid - 1] & sorted[mid] & U256::from(1))
+    };
+
+    Some(LatencyStats {
+        mean,
+        variance,
+        cv_bps,
+        median,
+        min: sorted[0],
+    })
+}
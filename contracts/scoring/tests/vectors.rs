@@ -0,0 +1,132 @@
+//! Checks the crate against every entry in `vectors.json`, so the rules and
+//! the published reference outputs cannot drift apart.
+
+use alloy_primitives::U256;
+use detective_scoring::{confidence, deception, humanity, stats};
+use serde_json::Value;
+
+fn vectors() -> Value {
+    serde_json::from_str(include_str!("../vectors.json")).expect("vectors.json is valid JSON")
+}
+
+/// Reads a field written either as a JSON number or a decimal string.
+fn u(entry: &Value, key: &str) -> U256 {
+    match &entry[key] {
+        Value::Number(n) => U256::from(n.as_u64().expect("unsigned integer")),
+        Value::String(s) => s.parse().expect("decimal string"),
+        other => panic!("{key}: unexpected {other}"),
+    }
+}
+
+fn entries<'a>(vectors: &'a Value, key: &str) -> &'a [Value] {
+    let list = vectors[key].as_array().expect("array of vectors");
+    assert!(!list.is_empty(), "{key} is empty");
+    list
+}
+
+#[test]
+fn defaults_match_the_crate() {
+    let vectors = vectors();
+    let d = &vectors["defaults"];
+    let rule = humanity::HumanityRule::default();
+    assert_eq!(u(d, "minAccuracyPct"), rule.min_accuracy_pct);
+    assert_eq!(u(d, "minLatencyMs"), rule.min_latency_ms);
+    assert_eq!(u(d, "maxLatencyMs"), rule.max_latency_ms);
+    assert_eq!(u(d, "wilsonZWad"), rule.wilson_z_wad);
+    assert_eq!(u(d, "minMatches"), rule.min_matches);
+    assert_eq!(
+        u(d, "minAccuracyLowerBoundBps"),
+        rule.min_accuracy_lower_bound_bps
+    );
+    assert_eq!(
+        u(d, "minLatencyCvBps"),
+        U256::from(humanity::DEFAULT_MIN_LATENCY_CV_BPS)
+    );
+    assert_eq!(
+        u(d, "minSingleLatencyMs"),
+        U256::from(humanity::DEFAULT_MIN_SINGLE_LATENCY_MS)
+    );
+    assert_eq!(u(d, "priorAlphaWad"), deception::DEFAULT_PRIOR_ALPHA_WAD);
+    assert_eq!(u(d, "priorBetaWad"), deception::DEFAULT_PRIOR_BETA_WAD);
+    assert_eq!(
+        u(d, "credibleZWad"),
+        U256::from(deception::DEFAULT_CREDIBLE_Z_WAD)
+    );
+}
+
+#[test]
+fn humanity_verdicts() {
+    let vectors = vectors();
+    let rule = humanity::HumanityRule::default();
+    for v in entries(&vectors, "humanityVerdicts") {
+        let verdict = rule.verdict(u(v, "correct"), u(v, "total"), u(v, "avgResponseTimeMs"));
+        assert_eq!(U256::from(verdict.code()), u(v, "code"), "{v}");
+    }
+}
+
+#[test]
+fn wilson_lower_bounds() {
+    let vectors = vectors();
+    for v in entries(&vectors, "wilsonLowerBounds") {
+        let bound =
+            confidence::wilson_lower_bound_bps(u(v, "correct"), u(v, "total"), u(v, "zWad"));
+        assert_eq!(bound, Some(u(v, "lowerBoundBps")), "{v}");
+    }
+}
+
+#[test]
+fn deception_ratings() {
+    let vectors = vectors();
+    for v in entries(&vectors, "deceptionRatings") {
+        let (fooled, total) = (u(v, "timesFooledHuman"), u(v, "totalInteractions"));
+        assert_eq!(
+            deception::rating_pct(fooled, total),
+            u(v, "ratingPct"),
+            "{v}"
+        );
+        assert_eq!(
+            deception::rating_bps(fooled, total),
+            u(v, "ratingBps"),
+            "{v}"
+        );
+        let (mean, lower) = deception::bayesian_rating_bps(
+            fooled,
+            total,
+            deception::DEFAULT_PRIOR_ALPHA_WAD,
+            deception::DEFAULT_PRIOR_BETA_WAD,
+            U256::from(deception::DEFAULT_CREDIBLE_Z_WAD),
+        );
+        assert_eq!(
+            (mean, lower),
+            (u(v, "meanBps"), u(v, "lowerBoundBps")),
+            "{v}"
+        );
+    }
+}
+
+#[test]
+fn latency_stats() {
+    let vectors = vectors();
+    for v in entries(&vectors, "latencyStats") {
+        let samples: Vec<U256> = v["samplesMs"]
+            .as_array()
+            .expect("samples")
+            .iter()
+            .map(|x| U256::from(x.as_u64().expect("ms")))
+            .collect();
+        let s = stats::latency_stats(&samples).expect("stats");
+        assert_eq!(s.mean, u(v, "mean"), "{v}");
+        assert_eq!(s.variance, u(v, "variance"), "{v}");
+        assert_eq!(s.cv_bps, u(v, "cvBps"), "{v}");
+        assert_eq!(s.median, u(v, "median"), "{v}");
+        assert_eq!(s.min, u(v, "min"), "{v}");
+
+        let ok = humanity::distribution_ok(
+            &s,
+            samples.len(),
+            U256::from(humanity::DEFAULT_MIN_LATENCY_CV_BPS),
+            U256::from(humanity::DEFAULT_MIN_SINGLE_LATENCY_MS),
+        );
+        assert_eq!(Some(ok), v["distributionOk"].as_bool(), "{v}");
+    }
+}
@@ -0,0 +1,192 @@
+//! Role-based access control, ABI-compatible with OpenZeppelin's
+//! `AccessControl`: roles are `bytes32` ids (`keccak256` of the role name),
+//! every role has an admin role whose holders can grant and revoke it, and
+//! `DEFAULT_ADMIN_ROLE` (zero) administers every role by default.
+//!
+//! As in `AccessControlDefaultAdminRules`, `DEFAULT_ADMIN_ROLE` itself has a
+//! single holder and cannot be granted, revoked, renounced or re-parented
+//! through the generic entrypoints; it only moves by the two-step
+//! `propose_admin` / `accept_admin` handover.
+
+use stylus_sdk::{
+    alloy_primitives::{b256, Address, B256},
+    evm,
+    prelude::*,
+    storage::{StorageB256, StorageBool, StorageMap},
+};
+
+use crate::errors::*;
+use crate::events::{RoleAdminChanged, RoleGranted, RoleRevoked};
+
+pub const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+/// `keccak256("RESULT_ORACLE_ROLE")`: reports match results and outcomes.
+pub const RESULT_ORACLE_ROLE: B256 =
+    b256!("fb00fa0c30af3f14d2520274f3a36283c33ef91fc8bc1f424b133d150af8e805");
+/// `keccak256("PAUSER_ROLE")`: pauses and unpauses registration and staking.
+pub const PAUSER_ROLE: B256 =
+    b256!("65d7a28e3265b37a6474929f336521b332c1681b933f6cb9f3376673440d862a");
+/// `keccak256("TREASURER_ROLE")`: sets fees and withdraws house funds.
+pub const TREASURER_ROLE: B256 =
+    b256!("3496e2e73c4d42b75d702e60d9e48102720b8691234415963a5a857b86425d07");
+/// `keccak256("THRESHOLD_MANAGER_ROLE")`: retunes scoring thresholds.
+pub const THRESHOLD_MANAGER_ROLE: B256 =
+    b256!("6e4baf62de573459cfb41cf9a6ca02b069db0854de9593d0e7dc90b651bab244");
+/// `keccak256("ARBITER_ROLE")`: rules on disputed match results.
+pub const ARBITER_ROLE: B256 =
+    b256!("bb08418a67729a078f87bbc8d02a770929bb68f5bfdf134ae2ead6ed38e2f4ae");
+
+/// Roles the admin receives at setup and hands over with `DEFAULT_ADMIN_ROLE`.
+pub const MANAGEMENT_ROLES: [B256; 3] = [THRESHOLD_MANAGER_ROLE, PAUSER_ROLE, TREASURER_ROLE];
+
+#[storage]
+pub struct RoleData {
+    members: StorageMap<Address, StorageBool>,
+    admin_role: StorageB256,
+}
+
+#[storage]
+pub struct AccessControl {
+    roles: StorageMap<B256, RoleData>,
+}
+
+/// Rejects `DEFAULT_ADMIN_ROLE`, which only moves by admin handover.
+pub fn ensure_not_default_admin(role: B256) -> Result<(), VerifierError> {
+    if role == DEFAULT_ADMIN_ROLE {
+        return Err(VerifierError::AccessControlEnforcedDefaultAdminRules(
+            AccessControlEnforcedDefaultAdminRules {},
+        ));
+    }
+    Ok(())
+}
+
+impl AccessControl {
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.roles.getter(role).members.get(account)
+    }
+
+    pub fn check_role(&self, role: B256, account: Address) -> Result<(), VerifierError> {
+        if !self.has_role(role, account) {
+            return Err(VerifierError::AccessControlUnauthorizedAccount(
+                AccessControlUnauthorizedAccount {
+                    account,
+                    neededRole: role,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn role_admin(&self, role: B256) -> B256 {
+        self.roles.getter(role).admin_role.get()
+    }
+
+    pub fn set_role_admin(&mut self, role: B256, admin_role: B256) {
+        let mut data = self.roles.setter(role);
+        let previous = data.admin_role.get();
+        data.admin_role.set(admin_role);
+        evm::log(RoleAdminChanged {
+            role,
+            previousAdminRole: previous,
+            newAdminRole: admin_role,
+        });
+    }
+
+    /// Grants `role` to `account`; returns whether anything changed.
+    pub fn grant(&mut self, role: B256, account: Address, sender: Address) -> bool {
+        let mut data = self.roles.setter(role);
+        if data.members.get(account) {
+            return false;
+        }
+        data.members.setter(account).set(true);
+        evm::log(RoleGranted {
+            role,
+            account,
+            sender,
+        });
+        true
+    }
+
+    /// Revokes `role` from `account`; returns whether anything changed.
+    pub fn revoke(&mut self, role: B256, account: Address, sender: Address) -> bool {
+        let mut data = self.roles.setter(role);
+        if !data.members.get(account) {
+            return false;
+        }
+        data.members.setter(account).set(false);
+        evm::log(RoleRevoked {
+            role,
+            account,
+            sender,
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::{
+        alloy_primitives::{address, Address, B256},
+        crypto::keccak,
+    };
+
+    use super::*;
+
+    const ADMIN: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+
+    #[test]
+    fn role_ids_hash_their_names() {
+        for (role, name) in [
+            (RESULT_ORACLE_ROLE, "RESULT_ORACLE_ROLE"),
+            (PAUSER_ROLE, "PAUSER_ROLE"),
+            (TREASURER_ROLE, "TREASURER_ROLE"),
+            (THRESHOLD_MANAGER_ROLE, "THRESHOLD_MANAGER_ROLE"),
+            (ARBITER_ROLE, "ARBITER_ROLE"),
+        ] {
+            assert_eq!(role, keccak(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn default_admin_role_is_off_limits() {
+        assert!(matches!(
+            ensure_not_default_admin(DEFAULT_ADMIN_ROLE),
+            Err(VerifierError::AccessControlEnforcedDefaultAdminRules(_))
+        ));
+        for role in MANAGEMENT_ROLES {
+            assert!(ensure_not_default_admin(role).is_ok());
+        }
+    }
+
+    #[motsu::test]
+    fn grants_and_revokes_once(contract: AccessControl) {
+        assert!(contract.grant(PAUSER_ROLE, BOB, ADMIN));
+        assert!(!contract.grant(PAUSER_ROLE, BOB, ADMIN));
+        assert!(contract.has_role(PAUSER_ROLE, BOB));
+        assert!(!contract.has_role(TREASURER_ROLE, BOB));
+
+        assert!(contract.revoke(PAUSER_ROLE, BOB, ADMIN));
+        assert!(!contract.revoke(PAUSER_ROLE, BOB, ADMIN));
+        assert!(!contract.has_role(PAUSER_ROLE, BOB));
+    }
+
+    #[motsu::test]
+    fn check_role_names_the_missing_role(contract: AccessControl) {
+        contract.grant(ARBITER_ROLE, BOB, ADMIN);
+        assert!(contract.check_role(ARBITER_ROLE, BOB).is_ok());
+        assert!(matches!(
+            contract.check_role(ARBITER_ROLE, ADMIN),
+            Err(VerifierError::AccessControlUnauthorizedAccount(
+                AccessControlUnauthorizedAccount { account, neededRole }
+            )) if account == ADMIN && neededRole == ARBITER_ROLE
+        ));
+    }
+
+    #[motsu::test]
+    fn roles_default_to_the_default_admin(contract: AccessControl) {
+        assert_eq!(contract.role_admin(ARBITER_ROLE), DEFAULT_ADMIN_ROLE);
+        contract.set_role_admin(ARBITER_ROLE, PAUSER_ROLE);
+        assert_eq!(contract.role_admin(ARBITER_ROLE), PAUSER_ROLE);
+        assert_eq!(contract.role_admin(PAUSER_ROLE), B256::ZERO);
+    }
+}
@@ -0,0 +1,160 @@
+//! EIP-712 performance attestations signed by the game server.
+//!
+//! The backend signs a `PerformanceAttestation` for a wallet at the end of a
+//! cycle. The contract rebuilds the typed-data digest and recovers the signer
+//! through the `ecrecover` precompile, so only numbers the server vouched for
+//! can reach the humanity rule.
+
+use alloc::borrow::Cow;
+use alloy_sol_types::{sol, Eip712Domain, SolStruct};
+use stylus_sdk::{
+    alloy_primitives::{address, Address, B256, U256},
+    block,
+    call::{static_call, Call},
+    contract,
+};
+
+sol! {
+    struct PerformanceAttestation {
+        address wallet;
+        uint256 cycleId;
+        uint256 correctGuesses;
+        uint256 totalMatches;
+        uint256 avgResponseTimeMs;
+        uint256 nonce;
+        uint256 expiry;
+    }
+}
+
+/// The `ecrecover` precompile.
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+/// Upper bound for `s` (secp256k1n / 2), rejecting malleable signatures.
+const SECP256K1_HALF_N: U256 = U256::from_limbs([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Domain bound to this deployment and chain.
+pub fn domain() -> Eip712Domain {
+    Eip712Domain::new(
+        Some(Cow::Borrowed("DetectiveStylusVerifier")),
+        Some(Cow::Borrowed("1")),
+        Some(U256::from(block::chainid())),
+        Some(contract::address()),
+        None,
+    )
+}
+
+/// Digest the game server is expected to have signed.
+pub fn signing_hash(attestation: &PerformanceAttestation) -> B256 {
+    attestation.eip712_signing_hash(&domain())
+}
+
+/// Recovers the signer of `digest` from a 65-byte `r || s || v` signature.
+/// Returns `None` for malformed, malleable or unrecoverable signatures.
+pub fn recover_signer(digest: B256, signature: &[u8]) -> Option<Address> {
+    let input = ecrecover_input(digest, signature)?;
+    let output = static_call(Call::new(), ECRECOVER, &input).ok()?;
+    if output.len() != 32 {
+        return None;
+    }
+
+    let signer = Address::from_slice(&output[12..]);
+    (signer != Address::ZERO).then_some(signer)
+}
+
+/// Builds the `ecrecover` input `hash || v || r || s`, each left-padded to
+/// 32 bytes, accepting `v` as 0/1 or 27/28. Returns `None` for a signature
+/// of the wrong length, a high `s` or any other `v`.
+fn ecrecover_input(digest: B256, signature: &[u8]) -> Option<[u8; 128]> {
+    if signature.len() != 65 {
+        return None;
+    }
+
+    let s = U256::from_be_slice(&signature[32..64]);
+    if s > SECP256K1_HALF_N {
+        return None;
+    }
+
+    let v = match signature[64] {
+        0 | 1 => signature[64] + 27,
+        27 | 28 => signature[64],
+        _ => return None,
+    };
+
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(digest.as_slice());
+    input[63] = v;
+    input[64..128].copy_from_slice(&signature[..64]);
+    Some(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{uint, B256, U256};
+
+    use super::*;
+
+    const DIGEST: B256 = B256::repeat_byte(0xd1);
+    const R: B256 = B256::repeat_byte(0x0a);
+
+    fn signature(s: U256, v: u8) -> [u8; 65] {
+        let mut signature = [0u8; 65];
+        signature[..32].copy_from_slice(R.as_slice());
+        signature[32..64].copy_from_slice(&s.to_be_bytes::<32>());
+        signature[64] = v;
+        signature
+    }
+
+    #[test]
+    fn lays_out_hash_v_r_s() {
+        let s = U256::from(0x5eu64);
+        let input = ecrecover_input(DIGEST, &signature(s, 27)).unwrap();
+        assert_eq!(&input[..32], DIGEST.as_slice());
+        assert_eq!(input[32..63], [0u8; 31]);
+        assert_eq!(input[63], 27);
+        assert_eq!(&input[64..96], R.as_slice());
+        assert_eq!(input[96..], s.to_be_bytes::<32>());
+    }
+
+    #[test]
+    fn normalises_v() {
+        let s = U256::from(1);
+        for (v, expected) in [(0, 27), (1, 28), (27, 27), (28, 28)] {
+            let input = ecrecover_input(DIGEST, &signature(s, v)).unwrap();
+            assert_eq!(input[63], expected, "v = {v}");
+        }
+        for v in [2, 26, 29, 35, 255] {
+            assert!(
+                ecrecover_input(DIGEST, &signature(s, v)).is_none(),
+                "v = {v}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_high_s() {
+        assert!(ecrecover_input(DIGEST, &signature(SECP256K1_HALF_N, 27)).is_some());
+        assert!(
+            ecrecover_input(DIGEST, &signature(SECP256K1_HALF_N + U256::from(1), 27)).is_none()
+        );
+        assert!(ecrecover_input(DIGEST, &signature(U256::MAX, 27)).is_none());
+    }
+
+    #[test]
+    fn half_n_is_half_the_curve_order() {
+        let n = uint!(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141_U256);
+        assert_eq!(SECP256K1_HALF_N, n >> 1);
+    }
+
+    #[test]
+    fn rejects_the_wrong_length() {
+        let full = signature(U256::from(1), 27);
+        assert!(ecrecover_input(DIGEST, &full[..64]).is_none());
+        assert!(ecrecover_input(DIGEST, &[full.as_slice(), &[0]].concat()).is_none());
+        assert!(ecrecover_input(DIGEST, &[]).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+//! Prior used to smooth AI agents' deception ratings.
+//!
+//! A bot that fooled 1 of 1 humans should not outrank one that fooled 60 of
+//! 100. Ratings are read off a Beta posterior (see
+//! `detective_scoring::deception`) so small samples are pulled toward the
+//! prior and ranked by a conservative lower bound, as used for the Agent
+//! Arena leaderboard in `docs/RESEARCH_API.md`.
+
+use stylus_sdk::{alloy_primitives::U256, evm, prelude::*, storage::StorageU256};
+
+use detective_scoring::deception::{
+    self, DEFAULT_CREDIBLE_Z_WAD, DEFAULT_PRIOR_ALPHA_WAD, DEFAULT_PRIOR_BETA_WAD,
+};
+use detective_scoring::math::WAD;
+
+use crate::errors::{InvalidPrior, VerifierError};
+use crate::events::DeceptionPriorUpdated;
+
+/// Priors stronger than this many pseudo-encounters would drown real data.
+const MAX_PRIOR_WEIGHT: u64 = 1_000;
+
+#[storage]
+pub struct DeceptionPrior {
+    alpha_wad: StorageU256,
+    beta_wad: StorageU256,
+    z_wad: StorageU256,
+}
+
+impl DeceptionPrior {
+    /// Validates and stores the prior, emitting `DeceptionPriorUpdated`.
+    pub fn update(
+        &mut self,
+        alpha_wad: U256,
+        beta_wad: U256,
+        z_wad: U256,
+    ) -> Result<(), VerifierError> {
+        let max_weight = U256::from(MAX_PRIOR_WEIGHT) * WAD;
+        if alpha_wad == U256::ZERO
+            || beta_wad == U256::ZERO
+            || alpha_wad > max_weight
+            || beta_wad > max_weight
+            || z_wad > U256::from(5) * WAD
+        {
+            return Err(VerifierError::InvalidPrior(InvalidPrior {}));
+        }
+
+        self.alpha_wad.set(alpha_wad);
+        self.beta_wad.set(beta_wad);
+        self.z_wad.set(z_wad);
+
+        evm::log(DeceptionPriorUpdated {
+            alphaWad: alpha_wad,
+            betaWad: beta_wad,
+            zWad: z_wad,
+        });
+        Ok(())
+    }
+
+    pub fn set_defaults(&mut self) -> Result<(), VerifierError> {
+        self.update(
+            DEFAULT_PRIOR_ALPHA_WAD,
+            DEFAULT_PRIOR_BETA_WAD,
+            U256::from(DEFAULT_CREDIBLE_Z_WAD),
+        )
+    }
+
+    /// Returns `(alphaWad, betaWad, zWad)`.
+    pub fn get(&self) -> (U256, U256, U256) {
+        (self.alpha_wad.get(), self.beta_wad.get(), self.z_wad.get())
+    }
+
+    /// Returns `(meanBps, lowerBoundBps)`; zeros for inconsistent input.
+    pub fn rate(&self, times_fooled_human: U256, total_interactions: U256) -> (U256, U256) {
+        deception::bayesian_rating_bps(
+            times_fooled_human,
+            total_interactions,
+            self.alpha_wad.get(),
+            self.beta_wad.get(),
+            self.z_wad.get(),
+        )
+    }
+}
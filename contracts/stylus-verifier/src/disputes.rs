@@ -0,0 +1,556 @@
+//! Optimistic finality for match results.
+//!
+//! Revealing a match's sealed answer only *proposes* it. The result then
+//! sits in a challenge window during which one registered player may
+//! dispute it by posting a bond. An unchallenged result becomes final when
+//! the window closes; a disputed one becomes final when the arbiter rules.
+//! The bond goes to whoever the ruling favours: back to the challenger if
+//! the proposal is overturned, to the proposer if it stands. Stake payouts
+//! and vote tallies only ever read final results.
+//!
+//! The ruling is trusted as given. A proposal has already been checked
+//! against the match's sealed answer (see `voting`), so overturning it means
+//! the arbiter contradicts the seal. That is allowed, so that a wrongly
+//! sealed answer can be corrected. A challenger can therefore only win if the
+//! arbiter overrides the seal.
+//!
+//! Each proposal's challenge deadline is fixed when it is made, so a later
+//! change to the challenge period only affects future proposals.
+//!
+//! A result that is still not final `RESULT_TIMEOUT` after the reveal and
+//! challenge windows (the answer was withheld, or a dispute was never ruled
+//! on) lets anyone void the match: stakes and any pending bond are refunded
+//! and its votes are dropped ungraded. Voiding is permanent.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    evm,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageU256},
+};
+
+use crate::errors::*;
+use crate::events::{
+    DisputeParamsUpdated, DisputeResolved, MatchVoided, ResultDisputed, ResultProposed,
+};
+
+/// Default challenge window: 1 day.
+pub const DEFAULT_CHALLENGE_PERIOD: u64 = 24 * 60 * 60;
+/// Shortest configurable challenge window: 1 hour, enough to notice and
+/// dispute a bad proposal.
+pub const MIN_CHALLENGE_PERIOD: u64 = 60 * 60;
+/// Longest configurable challenge window: 7 days.
+pub const MAX_CHALLENGE_PERIOD: u64 = 7 * 24 * 60 * 60;
+/// Grace for the oracle and arbiter after reveals close and the challenge
+/// window passes: 7 days.
+pub const RESULT_TIMEOUT: u64 = 7 * 24 * 60 * 60;
+/// Default dispute bond: 0.01 ether.
+pub const DEFAULT_DISPUTE_BOND: U256 = U256::from_limbs([10_000_000_000_000_000, 0, 0, 0]);
+/// Smallest configurable dispute bond: 0.001 ether, so disputes are never free.
+pub const MIN_DISPUTE_BOND: U256 = U256::from_limbs([1_000_000_000_000_000, 0, 0, 0]);
+
+#[storage]
+pub struct ResultRecord {
+    /// Zero means no result proposed.
+    proposed_at: StorageU256,
+    proposer: StorageAddress,
+    /// Last second the proposal can be disputed, fixed when proposed.
+    challenge_deadline: StorageU256,
+    /// Proposed answer, replaced by the arbiter's ruling if disputed.
+    opponent_is_bot: StorageBool,
+    /// Zero means undisputed.
+    challenger: StorageAddress,
+    bond: StorageU256,
+    resolved: StorageBool,
+    /// Timed out without a final result; never becomes final.
+    voided: StorageBool,
+}
+
+#[storage]
+pub struct OptimisticResults {
+    results: StorageMap<B256, ResultRecord>,
+    /// Seconds a proposal can be disputed for.
+    challenge_period: StorageU256,
+    /// Minimum native bond to dispute, in wei.
+    dispute_bond: StorageU256,
+}
+
+impl OptimisticResults {
+    pub fn set_defaults(&mut self) {
+        self.challenge_period
+            .set(U256::from(DEFAULT_CHALLENGE_PERIOD));
+        self.dispute_bond.set(DEFAULT_DISPUTE_BOND);
+    }
+
+    pub fn update(
+        &mut self,
+        challenge_period: U256,
+        dispute_bond: U256,
+    ) -> Result<(), VerifierError> {
+        if challenge_period < U256::from(MIN_CHALLENGE_PERIOD)
+            || challenge_period > U256::from(MAX_CHALLENGE_PERIOD)
+        {
+            return Err(VerifierError::InvalidPeriod(InvalidPeriod {}));
+        }
+        if dispute_bond < MIN_DISPUTE_BOND {
+            return Err(VerifierError::InvalidBond(InvalidBond {}));
+        }
+        self.challenge_period.set(challenge_period);
+        self.dispute_bond.set(dispute_bond);
+        evm::log(DisputeParamsUpdated {
+            challengePeriod: challenge_period,
+            disputeBond: dispute_bond,
+        });
+        Ok(())
+    }
+
+    /// Returns `(challengePeriod, disputeBond)`.
+    pub fn params(&self) -> (U256, U256) {
+        (self.challenge_period.get(), self.dispute_bond.get())
+    }
+
+    pub fn propose(
+        &mut self,
+        match_id: B256,
+        proposer: Address,
+        opponent_is_bot: bool,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        self.ensure_not_voided(match_id)?;
+        let challenge_deadline = now + self.challenge_period.get();
+        let mut result = self.results.setter(match_id);
+        result.proposed_at.set(now);
+        result.proposer.set(proposer);
+        result.challenge_deadline.set(challenge_deadline);
+        result.opponent_is_bot.set(opponent_is_bot);
+
+        evm::log(ResultProposed {
+            matchId: match_id,
+            opponentIsBot: opponent_is_bot,
+            challengeDeadline: challenge_deadline,
+        });
+        Ok(())
+    }
+
+    pub fn dispute(
+        &mut self,
+        match_id: B256,
+        challenger: Address,
+        bond: U256,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        self.ensure_not_voided(match_id)?;
+        let required = self.dispute_bond.get();
+        let deadline = self.challenge_deadline(match_id)?;
+        let mut result = self.results.setter(match_id);
+        if result.challenger.get() != Address::ZERO {
+            return Err(VerifierError::AlreadyDisputed(AlreadyDisputed {
+                matchId: match_id,
+            }));
+        }
+        if now > deadline {
+            return Err(VerifierError::ChallengeWindowClosed(
+                ChallengeWindowClosed {},
+            ));
+        }
+        if bond < required {
+            return Err(VerifierError::InsufficientBond(InsufficientBond {
+                required,
+            }));
+        }
+        result.challenger.set(challenger);
+        result.bond.set(bond);
+
+        evm::log(ResultDisputed {
+            matchId: match_id,
+            challenger,
+            bond,
+        });
+        Ok(())
+    }
+
+    /// Rules on a disputed result. Returns who the bond goes to and how much.
+    pub fn resolve(
+        &mut self,
+        match_id: B256,
+        opponent_is_bot: bool,
+    ) -> Result<(Address, U256), VerifierError> {
+        self.ensure_not_voided(match_id)?;
+        let mut result = self.results.setter(match_id);
+        let challenger = result.challenger.get();
+        if challenger == Address::ZERO {
+            return Err(VerifierError::NotDisputed(NotDisputed {
+                matchId: match_id,
+            }));
+        }
+        if result.resolved.get() {
+            return Err(VerifierError::MatchAlreadySettled(MatchAlreadySettled {
+                matchId: match_id,
+            }));
+        }
+        let challenger_won = opponent_is_bot != result.opponent_is_bot.get();
+        result.resolved.set(true);
+        result.opponent_is_bot.set(opponent_is_bot);
+
+        evm::log(DisputeResolved {
+            matchId: match_id,
+            opponentIsBot: opponent_is_bot,
+            challengerWon: challenger_won,
+        });
+        let winner = if challenger_won {
+            challenger
+        } else {
+            result.proposer.get()
+        };
+        Ok((winner, result.bond.get()))
+    }
+
+    /// The final answer for a match, or `None` while there is none yet.
+    pub fn outcome(&self, match_id: B256, now: U256) -> Option<bool> {
+        let result = self.results.getter(match_id);
+        let proposed_at = result.proposed_at.get();
+        if proposed_at == U256::ZERO || result.voided.get() {
+            return None;
+        }
+        let is_final = if result.challenger.get() == Address::ZERO {
+            now > result.challenge_deadline.get()
+        } else {
+            result.resolved.get()
+        };
+        is_final.then(|| result.opponent_is_bot.get())
+    }
+
+    /// Like `outcome`, but reverts with `MatchNotSettled` when not final.
+    pub fn final_outcome(&self, match_id: B256, now: U256) -> Result<bool, VerifierError> {
+        self.outcome(match_id, now)
+            .ok_or(VerifierError::MatchNotSettled(MatchNotSettled {
+                matchId: match_id,
+            }))
+    }
+
+    /// Voids a match whose reveals closed at `reveal_deadline` and that still
+    /// has no final result `RESULT_TIMEOUT` after the challenge window: the
+    /// proposal's own deadline if there is one, otherwise the current period
+    /// counted from `reveal_deadline`. Returns the challenger and bond to refund if a dispute was pending.
+    pub fn void(
+        &mut self,
+        match_id: B256,
+        reveal_deadline: U256,
+        now: U256,
+    ) -> Result<Option<(Address, U256)>, VerifierError> {
+        self.ensure_not_voided(match_id)?;
+        if self.outcome(match_id, now).is_some() {
+            return Err(VerifierError::MatchAlreadySettled(MatchAlreadySettled {
+                matchId: match_id,
+            }));
+        }
+        let proposal = self.results.getter(match_id);
+        let window_end = if proposal.proposed_at.get() == U256::ZERO {
+            reveal_deadline + self.challenge_period.get()
+        } else {
+            proposal.challenge_deadline.get()
+        };
+        let voidable_at = window_end + U256::from(RESULT_TIMEOUT);
+        if now <= voidable_at {
+            return Err(VerifierError::VoidTooEarly(VoidTooEarly {
+                voidableAt: voidable_at,
+            }));
+        }
+
+        let mut result = self.results.setter(match_id);
+        result.voided.set(true);
+        evm::log(MatchVoided { matchId: match_id });
+
+        let challenger = result.challenger.get();
+        Ok((challenger != Address::ZERO).then(|| (challenger, result.bond.get())))
+    }
+
+    pub fn is_voided(&self, match_id: B256) -> bool {
+        self.results.getter(match_id).voided.get()
+    }
+
+    /// Returns `(proposedAt, proposer, challenger, bond, resolved)`.
+    pub fn result_of(&self, match_id: B256) -> (U256, Address, Address, U256, bool) {
+        let result = self.results.getter(match_id);
+        (
+            result.proposed_at.get(),
+            result.proposer.get(),
+            result.challenger.get(),
+            result.bond.get(),
+            result.resolved.get(),
+        )
+    }
+
+    fn ensure_not_voided(&self, match_id: B256) -> Result<(), VerifierError> {
+        if self.is_voided(match_id) {
+            return Err(VerifierError::MatchVoid(MatchVoid { matchId: match_id }));
+        }
+        Ok(())
+    }
+
+    fn challenge_deadline(&self, match_id: B256) -> Result<U256, VerifierError> {
+        let result = self.results.getter(match_id);
+        if result.proposed_at.get() == U256::ZERO {
+            return Err(VerifierError::MatchNotSettled(MatchNotSettled {
+                matchId: match_id,
+            }));
+        }
+        Ok(result.challenge_deadline.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const PROPOSER: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const CHALLENGER: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+    const MATCH: B256 = B256::repeat_byte(0x11);
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[motsu::test]
+    fn arbiter_can_overturn_the_sealed_answer(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        assert!(contract
+            .dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, U256::from(2_000))
+            .is_ok());
+
+        let ruling = contract.resolve(MATCH, false);
+        assert!(matches!(ruling, Ok((winner, bond))
+            if winner == CHALLENGER && bond == DEFAULT_DISPUTE_BOND));
+        assert_eq!(contract.outcome(MATCH, U256::from(2_000)), Some(false));
+    }
+
+    #[motsu::test]
+    fn upheld_proposal_pays_the_bond_to_the_proposer(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        assert!(contract
+            .dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, U256::from(2_000))
+            .is_ok());
+        assert_eq!(contract.outcome(MATCH, U256::from(1_000 + 2 * DAY)), None);
+
+        let ruling = contract.resolve(MATCH, true);
+        assert!(matches!(ruling, Ok((winner, _)) if winner == PROPOSER));
+        assert_eq!(contract.outcome(MATCH, U256::from(2_000)), Some(true));
+    }
+
+    #[motsu::test]
+    fn dispute_needs_an_open_window_and_the_bond(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(matches!(
+            contract.dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, U256::from(900)),
+            Err(VerifierError::MatchNotSettled(_))
+        ));
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        let deadline = U256::from(1_000 + DAY);
+
+        assert!(matches!(
+            contract.dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND - U256::from(1), deadline),
+            Err(VerifierError::InsufficientBond(InsufficientBond { required }))
+                if required == DEFAULT_DISPUTE_BOND
+        ));
+        assert!(matches!(
+            contract.dispute(
+                MATCH,
+                CHALLENGER,
+                DEFAULT_DISPUTE_BOND,
+                deadline + U256::from(1)
+            ),
+            Err(VerifierError::ChallengeWindowClosed(_))
+        ));
+        assert!(contract
+            .dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, deadline)
+            .is_ok());
+        assert!(matches!(
+            contract.dispute(MATCH, PROPOSER, DEFAULT_DISPUTE_BOND, deadline),
+            Err(VerifierError::AlreadyDisputed(_))
+        ));
+        assert_eq!(
+            contract.result_of(MATCH),
+            (
+                U256::from(1_000),
+                PROPOSER,
+                CHALLENGER,
+                DEFAULT_DISPUTE_BOND,
+                false
+            )
+        );
+    }
+
+    #[motsu::test]
+    fn resolves_only_an_open_dispute_once(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        assert!(matches!(
+            contract.resolve(MATCH, true),
+            Err(VerifierError::NotDisputed(_))
+        ));
+        assert!(contract
+            .dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, U256::from(2_000))
+            .is_ok());
+        assert!(contract.resolve(MATCH, true).is_ok());
+        assert!(matches!(
+            contract.resolve(MATCH, false),
+            Err(VerifierError::MatchAlreadySettled(_))
+        ));
+        assert!(matches!(
+            contract.final_outcome(MATCH, U256::from(2_000)),
+            Ok(true)
+        ));
+    }
+
+    #[motsu::test]
+    fn unchallenged_result_is_final_after_the_window(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(matches!(
+            contract.final_outcome(MATCH, U256::from(1_000)),
+            Err(VerifierError::MatchNotSettled(_))
+        ));
+        assert!(contract
+            .propose(MATCH, PROPOSER, false, U256::from(1_000))
+            .is_ok());
+        let deadline = U256::from(1_000 + DAY);
+        assert_eq!(contract.outcome(MATCH, deadline), None);
+        assert_eq!(
+            contract.outcome(MATCH, deadline + U256::from(1)),
+            Some(false)
+        );
+    }
+
+    #[motsu::test]
+    fn voids_a_withheld_result_after_the_timeout(contract: OptimisticResults) {
+        contract.set_defaults();
+        let reveal_deadline = U256::from(1_000);
+        let voidable_at = U256::from(1_000 + DAY + RESULT_TIMEOUT);
+        assert!(matches!(
+            contract.void(MATCH, reveal_deadline, voidable_at),
+            Err(VerifierError::VoidTooEarly(VoidTooEarly { voidableAt })) if voidableAt == voidable_at
+        ));
+
+        let voided = contract.void(MATCH, reveal_deadline, voidable_at + U256::from(1));
+        assert!(matches!(voided, Ok(None)));
+        assert!(contract.is_voided(MATCH));
+        assert!(matches!(
+            contract.void(MATCH, reveal_deadline, voidable_at + U256::from(2)),
+            Err(VerifierError::MatchVoid(_))
+        ));
+        assert!(matches!(
+            contract.propose(MATCH, PROPOSER, true, voidable_at + U256::from(2)),
+            Err(VerifierError::MatchVoid(_))
+        ));
+        assert_eq!(contract.outcome(MATCH, voidable_at + U256::from(2)), None);
+    }
+
+    #[motsu::test]
+    fn voids_an_unresolved_dispute_and_returns_its_bond(contract: OptimisticResults) {
+        contract.set_defaults();
+        let reveal_deadline = U256::from(1_000);
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(5_000))
+            .is_ok());
+        assert!(contract
+            .dispute(MATCH, CHALLENGER, DEFAULT_DISPUTE_BOND, U256::from(6_000))
+            .is_ok());
+
+        // Counted from the proposal's own deadline, not the reveal deadline.
+        let voidable_at = U256::from(5_000 + DAY + RESULT_TIMEOUT);
+        assert!(matches!(
+            contract.void(MATCH, reveal_deadline, voidable_at),
+            Err(VerifierError::VoidTooEarly(_))
+        ));
+        let voided = contract.void(MATCH, reveal_deadline, voidable_at + U256::from(1));
+        assert!(matches!(voided, Ok(Some((challenger, bond)))
+            if challenger == CHALLENGER && bond == DEFAULT_DISPUTE_BOND));
+        assert!(matches!(
+            contract.resolve(MATCH, false),
+            Err(VerifierError::MatchVoid(_))
+        ));
+        assert_eq!(contract.outcome(MATCH, voidable_at + U256::from(1)), None);
+    }
+
+    #[motsu::test]
+    fn cannot_void_a_final_result(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        let late = U256::from(1_000 + 2 * DAY + RESULT_TIMEOUT);
+        assert!(matches!(
+            contract.void(MATCH, U256::from(1_000), late),
+            Err(VerifierError::MatchAlreadySettled(_))
+        ));
+        assert!(!contract.is_voided(MATCH));
+        assert_eq!(contract.outcome(MATCH, late), Some(true));
+    }
+
+    #[motsu::test]
+    fn bounds_the_dispute_params(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(matches!(
+            contract.update(U256::ZERO, DEFAULT_DISPUTE_BOND),
+            Err(VerifierError::InvalidPeriod(_))
+        ));
+        assert!(matches!(
+            contract.update(U256::from(MIN_CHALLENGE_PERIOD - 1), DEFAULT_DISPUTE_BOND),
+            Err(VerifierError::InvalidPeriod(_))
+        ));
+        assert!(matches!(
+            contract.update(U256::from(MAX_CHALLENGE_PERIOD + 1), DEFAULT_DISPUTE_BOND),
+            Err(VerifierError::InvalidPeriod(_))
+        ));
+        assert!(matches!(
+            contract.update(U256::from(DAY), U256::ZERO),
+            Err(VerifierError::InvalidBond(_))
+        ));
+        assert!(matches!(
+            contract.update(U256::from(DAY), MIN_DISPUTE_BOND - U256::from(1)),
+            Err(VerifierError::InvalidBond(_))
+        ));
+        assert_eq!(
+            contract.params(),
+            (U256::from(DEFAULT_CHALLENGE_PERIOD), DEFAULT_DISPUTE_BOND)
+        );
+
+        assert!(contract
+            .update(U256::from(MIN_CHALLENGE_PERIOD), MIN_DISPUTE_BOND)
+            .is_ok());
+        assert_eq!(
+            contract.params(),
+            (U256::from(MIN_CHALLENGE_PERIOD), MIN_DISPUTE_BOND)
+        );
+    }
+
+    #[motsu::test]
+    fn period_changes_do_not_move_a_pending_deadline(contract: OptimisticResults) {
+        contract.set_defaults();
+        assert!(contract
+            .propose(MATCH, PROPOSER, true, U256::from(1_000))
+            .is_ok());
+        let deadline = U256::from(1_000 + DAY);
+
+        assert!(contract
+            .update(U256::from(7 * DAY), DEFAULT_DISPUTE_BOND)
+            .is_ok());
+        assert_eq!(
+            contract.outcome(MATCH, deadline + U256::from(1)),
+            Some(true)
+        );
+
+        assert!(contract
+            .update(U256::from(DAY / 2), DEFAULT_DISPUTE_BOND)
+            .is_ok());
+        assert_eq!(contract.outcome(MATCH, deadline), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Elo ratings for human detectives and AI bots.
+//!
+//! Every encounter is a game between a human and a bot: the human wins by
+//! identifying the bot, the bot wins by passing as human. Catching a bot
+//! that fools most players therefore earns more than catching an easy one.
+//! Ratings are stored in WAD so small K-factor adjustments are not lost to
+//! rounding.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, I256, U256},
+    prelude::*,
+    storage::{StorageMap, StorageU256},
+};
+
+use detective_scoring::math::{self, Rounding, WAD};
+
+/// Rating every participant starts from.
+pub const INITIAL_RATING: u64 = 1_500;
+pub const DEFAULT_K_FACTOR: u64 = 32;
+pub const MAX_K_FACTOR: u64 = 128;
+
+/// Elo logistic scale: a 400-point gap means 10:1 odds.
+const SCALE: u64 = 400;
+
+#[storage]
+pub struct EloRatings {
+    /// Rating in WAD; zero means unrated (reads as `INITIAL_RATING`).
+    human_rating: StorageMap<Address, StorageU256>,
+    human_matches: StorageMap<Address, StorageU256>,
+    bot_rating: StorageMap<B256, StorageU256>,
+    bot_matches: StorageMap<B256, StorageU256>,
+    /// Maximum rating change per encounter, in whole points.
+    k_factor: StorageU256,
+}
+
+impl EloRatings {
+    pub fn k_factor(&self) -> U256 {
+        self.k_factor.get()
+    }
+
+    pub fn set_k_factor(&mut self, k: U256) {
+        self.k_factor.set(k);
+    }
+
+    /// Returns `(ratingWad, matches)`.
+    pub fn human(&self, human: Address) -> (U256, U256) {
+        (
+            or_initial(self.human_rating.get(human)),
+            self.human_matches.get(human),
+        )
+    }
+
+    /// Returns `(ratingWad, matches)`.
+    pub fn bot(&self, bot_id: B256) -> (U256, U256) {
+        (
+            or_initial(self.bot_rating.get(bot_id)),
+            self.bot_matches.get(bot_id),
+        )
+    }
+
+    /// Applies one encounter and returns the new `(humanWad, botWad)` ratings.
+    pub fn record(
+        &mut self,
+        human: Address,
+        bot_id: B256,
+        guessed_correctly: bool,
+    ) -> Option<(U256, U256)> {
+        let (human_rating, human_matches) = self.human(human);
+        let (bot_rating, bot_matches) = self.bot(bot_id);
+
+        let expected = expected_score(human_rating, bot_rating)?;
+        let k = self.k_factor.get();
+        // The bot's change mirrors the human's, so the pool stays zero-sum.
+        let (new_human, new_bot) = if guessed_correctly {
+            let delta = k.checked_mul(WAD - expected)?;
+            (
+                human_rating.checked_add(delta)?,
+                bot_rating.saturating_sub(delta),
+            )
+        } else {
+            let delta = k.checked_mul(expected)?;
+            (
+                human_rating.saturating_sub(delta),
+                bot_rating.checked_add(delta)?,
+            )
+        };
+
+        self.human_rating.setter(human).set(nonzero(new_human));
+        self.human_matches
+            .setter(human)
+            .set(human_matches + U256::from(1));
+        self.bot_rating.setter(bot_id).set(nonzero(new_bot));
+        self.bot_matches
+            .setter(bot_id)
+            .set(bot_matches + U256::from(1));
+
+        Some((new_human, new_bot))
+    }
+}
+
+/// Probability (WAD) that a player rated `a` beats one rated `b`:
+/// `1 / (1 + 10^((b − a) / 400))`.
+pub fn expected_score(a: U256, b: U256) -> Option<U256> {
+    let a = I256::try_from(a).ok()?;
+    let b = I256::try_from(b).ok()?;
+    let exponent = (b - a) / I256::try_from(SCALE).ok()?;
+    let odds = math::pow_wad(U256::from(10) * WAD, exponent)?;
+    math::div_wad(WAD, WAD.checked_add(odds)?, Rounding::Nearest)
+}
+
+fn or_initial(rating: U256) -> U256 {
+    if rating == U256::ZERO {
+        U256::from(INITIAL_RATING) * WAD
+    } else {
+        rating
+    }
+}
+
+/// A rating that bottoms out at zero is stored as 1 wei so it does not read
+/// back as "unrated".
+fn nonzero(rating: U256) -> U256 {
+    rating.max(U256::from(1))
+}
@@ -0,0 +1,174 @@
+//! Game registration and entry-fee escrow, ported from
+//! `DetectiveGameEntryV4.registerForGame`.
+//!
+//! Behaviour matches the Solidity contract: registration is one-shot per
+//! wallet, blocked while paused, and the fee (anything at or above
+//! `minEntryFee`) stays in the contract as house funds.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, evm,
+    prelude::*,
+    storage::{StorageBool, StorageMap, StorageU256},
+};
+
+use crate::errors::*;
+use crate::events::{MinEntryFeeUpdated, PauseStatusChanged, PlayerRegistered};
+
+#[storage]
+pub struct GameEntry {
+    /// Blocks registration (and staking) while set.
+    paused: StorageBool,
+    /// Minimum `msg.value` accepted by `register`, in wei.
+    min_entry_fee: StorageU256,
+    /// wallet => registered for the game.
+    registered: StorageMap<Address, StorageBool>,
+}
+
+impl GameEntry {
+    pub fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused.set(paused);
+        evm::log(PauseStatusChanged { isPaused: paused });
+    }
+
+    pub fn min_entry_fee(&self) -> U256 {
+        self.min_entry_fee.get()
+    }
+
+    pub fn set_min_entry_fee(&mut self, fee: U256) {
+        self.min_entry_fee.set(fee);
+        evm::log(MinEntryFeeUpdated { newFee: fee });
+    }
+
+    pub fn is_registered(&self, wallet: Address) -> bool {
+        self.registered.get(wallet)
+    }
+
+    pub fn when_not_paused(&self) -> Result<(), VerifierError> {
+        if self.paused.get() {
+            return Err(VerifierError::ContractPaused(ContractPaused {}));
+        }
+        Ok(())
+    }
+
+    pub fn only_registered(&self, wallet: Address) -> Result<(), VerifierError> {
+        if !self.registered.get(wallet) {
+            return Err(VerifierError::NotRegistered(NotRegistered {}));
+        }
+        Ok(())
+    }
+
+    /// Registers `wallet`, which paid `fee_paid` wei.
+    pub fn register(&mut self, wallet: Address, fee_paid: U256) -> Result<(), VerifierError> {
+        self.when_not_paused()?;
+        if self.registered.get(wallet) {
+            return Err(VerifierError::AlreadyRegistered(AlreadyRegistered {}));
+        }
+        if fee_paid < self.min_entry_fee.get() {
+            return Err(VerifierError::InsufficientFee(InsufficientFee {}));
+        }
+
+        self.registered.setter(wallet).set(true);
+
+        evm::log(PlayerRegistered {
+            wallet,
+            timestamp: U256::from(block::timestamp()),
+            feePaid: fee_paid,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+
+    #[motsu::test]
+    fn registers_at_the_minimum_fee(contract: GameEntry) {
+        contract.set_min_entry_fee(U256::from(100));
+        assert!(contract.register(ALICE, U256::from(100)).is_ok());
+        assert!(contract.is_registered(ALICE));
+        assert!(!contract.is_registered(BOB));
+        assert!(contract.only_registered(ALICE).is_ok());
+    }
+
+    #[motsu::test]
+    fn rejects_a_second_registration(contract: GameEntry) {
+        assert!(contract.register(ALICE, U256::ZERO).is_ok());
+        assert!(matches!(
+            contract.register(ALICE, U256::from(1)),
+            Err(VerifierError::AlreadyRegistered(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn rejects_a_fee_below_the_minimum(contract: GameEntry) {
+        contract.set_min_entry_fee(U256::from(100));
+        assert!(matches!(
+            contract.register(ALICE, U256::from(99)),
+            Err(VerifierError::InsufficientFee(_))
+        ));
+        assert!(!contract.is_registered(ALICE));
+    }
+
+    #[motsu::test]
+    fn rejects_registration_while_paused(contract: GameEntry) {
+        contract.set_paused(true);
+        assert!(matches!(
+            contract.register(ALICE, U256::ZERO),
+            Err(VerifierError::ContractPaused(_))
+        ));
+
+        contract.set_paused(false);
+        assert!(contract.register(ALICE, U256::ZERO).is_ok());
+    }
+
+    #[motsu::test]
+    fn pause_is_checked_before_duplicates_and_fee(contract: GameEntry) {
+        // Same order as `whenNotPaused` followed by the body checks in V4.
+        assert!(contract.register(ALICE, U256::ZERO).is_ok());
+        contract.set_min_entry_fee(U256::from(100));
+        assert!(matches!(
+            contract.register(ALICE, U256::ZERO),
+            Err(VerifierError::AlreadyRegistered(_))
+        ));
+        contract.set_paused(true);
+        assert!(matches!(
+            contract.register(ALICE, U256::ZERO),
+            Err(VerifierError::ContractPaused(_))
+        ));
+    }
+
+    #[test]
+    fn player_registered_matches_v4() {
+        // event PlayerRegistered(address indexed wallet, uint256 timestamp, uint256 feePaid)
+        assert_eq!(
+            PlayerRegistered::SIGNATURE,
+            "PlayerRegistered(address,uint256,uint256)"
+        );
+
+        let event = PlayerRegistered {
+            wallet: ALICE,
+            timestamp: U256::from(1_735_689_600u64),
+            feePaid: U256::from(100),
+        };
+        let [signature, wallet] = event.encode_topics_array::<2>();
+        assert_eq!(signature.0, PlayerRegistered::SIGNATURE_HASH);
+        assert_eq!(wallet.0, B256::left_padding_from(ALICE.as_slice()));
+
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(&U256::from(1_735_689_600u64).to_be_bytes::<32>());
+        data[32..].copy_from_slice(&U256::from(100).to_be_bytes::<32>());
+        assert_eq!(event.encode_data(), data);
+    }
+}
@@ -0,0 +1,182 @@
+//! Custom errors surfaced by the verifier contract.
+//!
+//! Names mirror the Solidity contracts where an equivalent exists so that
+//! frontends can decode reverts with a single ABI.
+
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+sol! {
+    error AlreadyInitialized();
+    error InvalidAddress();
+    error InvalidSignature();
+    error UnauthorizedSigner(address signer);
+    error AttestationExpired(uint256 expiry);
+    error NonceAlreadyUsed(address wallet, uint256 nonce);
+    error InvalidMerkleRoot();
+    error CycleRootAlreadySet(uint256 cycleId);
+    error UnknownCycle(uint256 cycleId);
+    error InvalidMerkleProof();
+    error InvalidPeriod();
+    error InvalidThresholds();
+    error TooManySamples(uint256 count, uint256 max);
+    error StatsOverflow();
+    error NoSamples();
+    error InvalidPrior();
+    error InvalidKFactor();
+    error RatingOverflow();
+    error ArrayLengthMismatch();
+    error TooManyEncounters(uint256 count, uint256 max);
+    error StalePeriod(uint256 period, uint256 currentPeriod);
+    error ModelAlreadyRegistered(bytes32 modelId);
+    error UnknownModel(bytes32 modelId);
+    error NotModelOperator(bytes32 modelId);
+    error InvalidOutcome();
+    error InvalidMatchId();
+    error InvalidDeadline();
+    error UnknownMatch(bytes32 matchId);
+    error MatchAlreadyOpen(bytes32 matchId);
+    error CommitWindowClosed();
+    error AlreadyVoted();
+    error RevealWindowClosed();
+    error RevealWindowOpen();
+    error NoCommitment();
+    error AlreadyRevealed();
+    error CommitmentMismatch();
+    error MatchAlreadySettled(bytes32 matchId);
+    error MatchNotSettled(bytes32 matchId);
+    error AlreadyTallied();
+    error InvalidCommitment();
+    error TruthMismatch(bytes32 matchId);
+    error ContractPaused();
+    error AlreadyRegistered();
+    error InsufficientFee();
+    error NotRegistered();
+    error ExpiredDeadline();
+    error StakeTooLow();
+    error StakeTooHigh();
+    error AlreadyStaked();
+    error TooManyStakers(uint256 max);
+    error USDCTransferFailed();
+    error InvalidFee();
+    error StakesAlreadySettled(bytes32 matchId);
+    error NoFundsToWithdraw();
+    error TransferFailed();
+    error ReentrantCall();
+    error InsufficientHouseFunds(uint256 available);
+    error AlreadyDisputed(bytes32 matchId);
+    error NotDisputed(bytes32 matchId);
+    error ChallengeWindowClosed();
+    error InsufficientBond(uint256 required);
+    error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+    error AccessControlBadConfirmation();
+    error UnknownChangeKind(uint8 kind);
+    error InvalidChangeParams();
+    error ChangeAlreadyQueued(bytes32 id);
+    error ChangeNotQueued(bytes32 id);
+    error ChangeNotReady(bytes32 id, uint256 eta);
+    error NotPendingAdmin();
+    error InconsistentStats(uint256 correct, uint256 total);
+    error InsufficientMatches(uint256 total, uint256 required);
+    error AccuracyTooLow(uint256 accuracyPct, uint256 required);
+    error ResponsesTooFast(uint256 avgMs, uint256 minMs);
+    error ResponsesTooSlow(uint256 avgMs, uint256 maxMs);
+    error LowConfidence(uint256 lowerBoundBps, uint256 required);
+    error BatchTooLarge(uint256 count, uint256 max);
+    error NotDeployer(address caller);
+    error UntalliedVotes(address wallet, uint256 pending);
+    error MatchVoid(bytes32 matchId);
+    error VoidTooEarly(uint256 voidableAt);
+    error AccessControlEnforcedDefaultAdminRules();
+    error InvalidBond();
+    error PeriodInProgress(uint256 period);
+    error NoPendingPeriod(uint256 period);
+}
+
+#[derive(SolidityError)]
+pub enum VerifierError {
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidAddress(InvalidAddress),
+    InvalidSignature(InvalidSignature),
+    UnauthorizedSigner(UnauthorizedSigner),
+    AttestationExpired(AttestationExpired),
+    NonceAlreadyUsed(NonceAlreadyUsed),
+    InvalidMerkleRoot(InvalidMerkleRoot),
+    CycleRootAlreadySet(CycleRootAlreadySet),
+    UnknownCycle(UnknownCycle),
+    InvalidMerkleProof(InvalidMerkleProof),
+    InvalidPeriod(InvalidPeriod),
+    InvalidThresholds(InvalidThresholds),
+    TooManySamples(TooManySamples),
+    StatsOverflow(StatsOverflow),
+    NoSamples(NoSamples),
+    InvalidPrior(InvalidPrior),
+    InvalidKFactor(InvalidKFactor),
+    RatingOverflow(RatingOverflow),
+    ArrayLengthMismatch(ArrayLengthMismatch),
+    TooManyEncounters(TooManyEncounters),
+    StalePeriod(StalePeriod),
+    ModelAlreadyRegistered(ModelAlreadyRegistered),
+    UnknownModel(UnknownModel),
+    NotModelOperator(NotModelOperator),
+    InvalidOutcome(InvalidOutcome),
+    InvalidMatchId(InvalidMatchId),
+    InvalidDeadline(InvalidDeadline),
+    UnknownMatch(UnknownMatch),
+    MatchAlreadyOpen(MatchAlreadyOpen),
+    CommitWindowClosed(CommitWindowClosed),
+    AlreadyVoted(AlreadyVoted),
+    RevealWindowClosed(RevealWindowClosed),
+    RevealWindowOpen(RevealWindowOpen),
+    NoCommitment(NoCommitment),
+    AlreadyRevealed(AlreadyRevealed),
+    CommitmentMismatch(CommitmentMismatch),
+    MatchAlreadySettled(MatchAlreadySettled),
+    MatchNotSettled(MatchNotSettled),
+    AlreadyTallied(AlreadyTallied),
+    InvalidCommitment(InvalidCommitment),
+    TruthMismatch(TruthMismatch),
+    ContractPaused(ContractPaused),
+    AlreadyRegistered(AlreadyRegistered),
+    InsufficientFee(InsufficientFee),
+    NotRegistered(NotRegistered),
+    ExpiredDeadline(ExpiredDeadline),
+    StakeTooLow(StakeTooLow),
+    StakeTooHigh(StakeTooHigh),
+    AlreadyStaked(AlreadyStaked),
+    TooManyStakers(TooManyStakers),
+    USDCTransferFailed(USDCTransferFailed),
+    InvalidFee(InvalidFee),
+    StakesAlreadySettled(StakesAlreadySettled),
+    NoFundsToWithdraw(NoFundsToWithdraw),
+    TransferFailed(TransferFailed),
+    ReentrantCall(ReentrantCall),
+    InsufficientHouseFunds(InsufficientHouseFunds),
+    AlreadyDisputed(AlreadyDisputed),
+    NotDisputed(NotDisputed),
+    ChallengeWindowClosed(ChallengeWindowClosed),
+    InsufficientBond(InsufficientBond),
+    AccessControlUnauthorizedAccount(AccessControlUnauthorizedAccount),
+    AccessControlBadConfirmation(AccessControlBadConfirmation),
+    UnknownChangeKind(UnknownChangeKind),
+    InvalidChangeParams(InvalidChangeParams),
+    ChangeAlreadyQueued(ChangeAlreadyQueued),
+    ChangeNotQueued(ChangeNotQueued),
+    ChangeNotReady(ChangeNotReady),
+    NotPendingAdmin(NotPendingAdmin),
+    InconsistentStats(InconsistentStats),
+    InsufficientMatches(InsufficientMatches),
+    AccuracyTooLow(AccuracyTooLow),
+    ResponsesTooFast(ResponsesTooFast),
+    ResponsesTooSlow(ResponsesTooSlow),
+    LowConfidence(LowConfidence),
+    BatchTooLarge(BatchTooLarge),
+    NotDeployer(NotDeployer),
+    UntalliedVotes(UntalliedVotes),
+    MatchVoid(MatchVoid),
+    VoidTooEarly(VoidTooEarly),
+    AccessControlEnforcedDefaultAdminRules(AccessControlEnforcedDefaultAdminRules),
+    InvalidBond(InvalidBond),
+    PeriodInProgress(PeriodInProgress),
+    NoPendingPeriod(NoPendingPeriod),
+}
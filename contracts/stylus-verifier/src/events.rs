@@ -0,0 +1,67 @@
+//! Events emitted by the verifier contract, for indexers and other dApps.
+
+use alloy_sol_types::sol;
+
+sol! {
+    event HumanityVerified(address indexed wallet, uint8 method, uint256 verifiedUntil);
+    event HumanityExpired(address indexed wallet, uint256 expiredAt);
+    event ThresholdsUpdated(
+        uint256 minAccuracyPct,
+        uint256 minLatencyMs,
+        uint256 maxLatencyMs,
+        uint256 version
+    );
+    event ConfidenceThresholdsUpdated(
+        uint256 wilsonZWad,
+        uint256 minMatches,
+        uint256 minAccuracyLowerBoundBps,
+        uint256 version
+    );
+    event DeceptionPriorUpdated(uint256 alphaWad, uint256 betaWad, uint256 zWad);
+    event DistributionThresholdsUpdated(
+        uint256 minLatencyCvBps,
+        uint256 minSingleLatencyMs,
+        uint256 version
+    );
+    event EloUpdated(address indexed human, bytes32 indexed botId, bool guessedCorrectly, uint256 humanRatingWad, uint256 botRatingWad);
+    event KFactorUpdated(uint256 kFactor);
+    event GlickoPeriodRated(uint256 indexed period, uint256 encounters);
+    event ModelRegistered(bytes32 indexed modelId, address indexed operator, bytes32 metadataHash);
+    event ModelMetadataUpdated(bytes32 indexed modelId, bytes32 metadataHash);
+    event ModelOperatorUpdated(bytes32 indexed modelId, address indexed operator);
+    event ModelOutcomesRecorded(bytes32 indexed modelId, uint256 encounters, uint256 fooled);
+    event PersonaOutcomesRecorded(uint256 indexed fid, uint256 attempts, uint256 successes);
+    event MatchOpened(bytes32 indexed matchId, bytes32 truthCommitment, uint256 commitDeadline, uint256 revealDeadline);
+    event VoteCommitted(address indexed wallet, bytes32 indexed matchId, bytes32 commitment);
+    event VoteRevealed(address indexed wallet, bytes32 indexed matchId, bool isBot);
+    event VoteTallied(address indexed wallet, bytes32 indexed matchId, bool correct, bool forfeit);
+    event PlayerRegistered(address indexed wallet, uint256 timestamp, uint256 feePaid);
+    event PauseStatusChanged(bool isPaused);
+    event MinEntryFeeUpdated(uint256 newFee);
+    event StakePlaced(address indexed wallet, bytes32 indexed matchId, bool isBot, uint256 amount, address token);
+    event HouseFeeUpdated(uint256 feeBps);
+    event StakesSettled(bytes32 indexed matchId, address indexed token, uint256 winningPool, uint256 losingPool, uint256 paidOut, uint256 houseTake);
+    event RewardsAllocated(address indexed token, uint256 totalAmount, uint256 recipientCount);
+    event Withdrawal(address indexed wallet, address indexed token, uint256 amount);
+    event HouseFundsWithdrawn(address indexed token, uint256 amount);
+    event HouseWalletUpdated(address indexed houseWallet);
+    event ResultProposed(bytes32 indexed matchId, bool opponentIsBot, uint256 challengeDeadline);
+    event ResultDisputed(bytes32 indexed matchId, address indexed challenger, uint256 bond);
+    event DisputeResolved(bytes32 indexed matchId, bool opponentIsBot, bool challengerWon);
+    event DisputeParamsUpdated(uint256 challengePeriod, uint256 disputeBond);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole);
+    event ChangeScheduled(bytes32 indexed id, uint8 indexed kind, bytes params, uint256 eta);
+    event ChangeCancelled(bytes32 indexed id);
+    event ChangeExecuted(bytes32 indexed id);
+    event TimelockDelayUpdated(uint256 delay);
+    event AdminTransferProposed(address indexed currentAdmin, address indexed pendingAdmin);
+    event AdminTransferred(address indexed oldAdmin, address indexed newAdmin);
+    event HumanityChecked(address indexed wallet, uint8 method, address indexed reporter, uint256 accuracyBps, uint256 avgLatency, bool passed, uint256 policyVersion);
+    event DeceptionRated(bytes32 indexed botId, uint8 method, address indexed reporter, uint256 fooled, uint256 total, uint256 ratingBps);
+    event MatchVoided(bytes32 indexed matchId);
+    event StakesRefunded(bytes32 indexed matchId, address indexed token, uint256 amount, uint256 recipientCount);
+    event VoteDiscarded(address indexed wallet, bytes32 indexed matchId);
+    event GlickoEncountersAdded(uint256 indexed period, uint256 added, uint256 pending);
+}
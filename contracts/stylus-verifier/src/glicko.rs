@@ -0,0 +1,584 @@
+//! Glicko-2 ratings with rating deviation and volatility.
+//!
+//! Elo treats a newcomer's 1500 as seriously as a veteran's. Glicko-2 also
+//! tracks how unsure it is (RD) and how erratic a participant is
+//! (volatility), so a few lucky cycles move a new player a lot but cannot
+//! vault them over established ones on a conservative `rating − 2·RD`
+//! leaderboard. Each game cycle is one rating period.
+//!
+//! A cycle can hold more encounters than fit in one call, so a period's
+//! encounters are accumulated first, over as many calls as needed, and
+//! rated in a separate step. Only one period accumulates at a time, and
+//! periods are rated in increasing order starting from any cycle id,
+//! including 0.
+//!
+//! Follows Glickman, "Example of the Glicko-2 system" (2013), in WAD
+//! fixed-point on the Glicko-2 scale (μ, φ, σ).
+
+use alloc::{vec, vec::Vec};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, I256, U256},
+    prelude::*,
+    storage::{
+        StorageAddress, StorageB256, StorageBool, StorageI256, StorageMap, StorageU256, StorageVec,
+    },
+};
+
+use detective_scoring::math::{self, div_wad_signed as div, mul_wad_signed as mul, WAD};
+
+use crate::errors::*;
+
+/// Upper bound on encounters added per call.
+pub const MAX_PERIOD_ENCOUNTERS: usize = 256;
+/// Upper bound on encounters in one period, keeping the rating step within
+/// gas. A full cycle (50 players × 5 rounds) is 250.
+pub const MAX_PENDING_ENCOUNTERS: usize = 1_024;
+
+/// Glicko-1 ↔ Glicko-2 scale factor, 400 / ln(10) ≈ 173.7178.
+const SCALE: i128 = 173_717_792_761_245_780_000;
+const INITIAL_RATING: i128 = 1_500_000_000_000_000_000_000;
+const INITIAL_RD: i128 = 350_000_000_000_000_000_000;
+const INITIAL_VOLATILITY: i128 = 60_000_000_000_000_000;
+/// System constant τ; constrains volatility change per period.
+const TAU: i128 = 500_000_000_000_000_000;
+/// 3 / π².
+const THREE_OVER_PI_SQUARED: i128 = 303_963_550_927_013_314;
+/// Convergence tolerance for the volatility iteration.
+const EPSILON: i128 = 1_000_000_000_000;
+/// Bound on volatility iterations; convergence normally takes under ten.
+const MAX_ITERATIONS: usize = 50;
+
+fn w(x: i128) -> I256 {
+    I256::try_from(x).unwrap_or(I256::ZERO)
+}
+
+fn one() -> I256 {
+    w(WAD.to::<i128>())
+}
+
+/// A participant's state on the Glicko-2 scale, in WAD.
+#[derive(Clone, Copy)]
+pub struct Rating {
+    pub mu: I256,
+    pub phi: I256,
+    pub sigma: I256,
+}
+
+impl Rating {
+    fn initial() -> Self {
+        Self {
+            mu: I256::ZERO,
+            phi: div(w(INITIAL_RD), w(SCALE)).unwrap_or(I256::ZERO),
+            sigma: w(INITIAL_VOLATILITY),
+        }
+    }
+
+    /// Returns `(ratingWad, rdWad, volatilityWad, conservativeWad)` on the
+    /// familiar Glicko scale, where `conservative = rating − 2·RD`.
+    pub fn to_public(self) -> Option<(I256, U256, U256, I256)> {
+        let rating = mul(self.mu, w(SCALE))?.checked_add(w(INITIAL_RATING))?;
+        let rd = mul(self.phi, w(SCALE))?;
+        let conservative = rating.checked_sub(rd.checked_mul(w(2))?)?;
+        Some((rating, rd.into_raw(), self.sigma.into_raw(), conservative))
+    }
+
+    /// Pre-period RD inflation for `periods` periods without games:
+    /// `φ' = √(φ² + n·σ²)`, capped at a newcomer's RD.
+    fn idle(self, periods: U256) -> Option<Self> {
+        if periods == U256::ZERO {
+            return Some(self);
+        }
+        let cap = Self::initial().phi;
+        let periods = I256::try_from(periods.min(U256::from(1_000))).ok()?;
+        let growth = mul(self.sigma, self.sigma)?.checked_mul(periods)?;
+        let phi = sqrt(mul(self.phi, self.phi)?.checked_add(growth)?)?.min(cap);
+        Some(Self { phi, ..self })
+    }
+}
+
+#[storage]
+pub struct GlickoRecord {
+    mu: StorageI256,
+    /// Zero means the participant has never been rated.
+    phi: StorageU256,
+    sigma: StorageU256,
+    /// Last period this record was updated in.
+    last_period: StorageU256,
+}
+
+impl GlickoRecord {
+    /// Rating as of `period`, including idle RD growth since the last update.
+    fn rating_at(&self, period: U256) -> Option<Rating> {
+        if self.phi.get() == U256::ZERO {
+            return Some(Rating::initial());
+        }
+        let stored = Rating {
+            mu: self.mu.get(),
+            phi: I256::try_from(self.phi.get()).ok()?,
+            sigma: I256::try_from(self.sigma.get()).ok()?,
+        };
+        let idle = period
+            .saturating_sub(self.last_period.get())
+            .saturating_sub(U256::from(1));
+        stored.idle(idle)
+    }
+
+    fn set_rating(&mut self, rating: Rating, period: U256) {
+        self.mu.set(rating.mu);
+        self.phi.set(rating.phi.into_raw().max(U256::from(1)));
+        self.sigma.set(rating.sigma.into_raw());
+        self.last_period.set(period);
+    }
+}
+
+#[storage]
+pub struct Glicko2 {
+    humans: StorageMap<Address, GlickoRecord>,
+    bots: StorageMap<B256, GlickoRecord>,
+    /// Most recent rating period (game cycle) processed.
+    current_period: StorageU256,
+    /// Set once any period has been rated; until then `current_period` is
+    /// meaningless and every period id is acceptable.
+    rated_any: StorageBool,
+    /// Period whose encounters are being accumulated, while `pending_open`.
+    pending_period: StorageU256,
+    pending_open: StorageBool,
+    /// Encounters of the pending period, as parallel arrays.
+    pending_humans: StorageVec<StorageAddress>,
+    pending_bots: StorageVec<StorageB256>,
+    pending_wins: StorageVec<StorageBool>,
+}
+
+impl Glicko2 {
+    pub fn current_period(&self) -> U256 {
+        self.current_period.get()
+    }
+
+    pub fn rated_any(&self) -> bool {
+        self.rated_any.get()
+    }
+
+    /// Returns `(open, period, encounters)` for the period being accumulated.
+    pub fn pending(&self) -> (bool, U256, U256) {
+        (
+            self.pending_open.get(),
+            self.pending_period.get(),
+            U256::from(self.pending_humans.len()),
+        )
+    }
+
+    /// Queues encounters for `period`, opening it if no period is pending.
+    /// Entry `i` says whether `humans[i]` identified `bot_ids[i]`. Returns
+    /// the number of encounters now pending.
+    pub fn add_encounters(
+        &mut self,
+        period: U256,
+        humans: &[Address],
+        bot_ids: &[B256],
+        guessed_correctly: &[bool],
+    ) -> Result<usize, VerifierError> {
+        if humans.len() != bot_ids.len() || humans.len() != guessed_correctly.len() {
+            return Err(VerifierError::ArrayLengthMismatch(ArrayLengthMismatch {}));
+        }
+        if humans.len() > MAX_PERIOD_ENCOUNTERS {
+            return Err(VerifierError::TooManyEncounters(TooManyEncounters {
+                count: U256::from(humans.len()),
+                max: U256::from(MAX_PERIOD_ENCOUNTERS),
+            }));
+        }
+        if self.pending_open.get() {
+            let pending = self.pending_period.get();
+            if period != pending {
+                return Err(VerifierError::PeriodInProgress(PeriodInProgress {
+                    period: pending,
+                }));
+            }
+        } else {
+            let current = self.current_period.get();
+            if self.rated_any.get() && period <= current {
+                return Err(VerifierError::StalePeriod(StalePeriod {
+                    period,
+                    currentPeriod: current,
+                }));
+            }
+        }
+        let total = self.pending_humans.len() + humans.len();
+        if total > MAX_PENDING_ENCOUNTERS {
+            return Err(VerifierError::TooManyEncounters(TooManyEncounters {
+                count: U256::from(total),
+                max: U256::from(MAX_PENDING_ENCOUNTERS),
+            }));
+        }
+
+        self.pending_open.set(true);
+        self.pending_period.set(period);
+        for ((human, bot), won) in humans.iter().zip(bot_ids).zip(guessed_correctly) {
+            self.pending_humans.push(*human);
+            self.pending_bots.push(*bot);
+            self.pending_wins.push(*won);
+        }
+        Ok(total)
+    }
+
+    /// Rates the pending `period` from every encounter added to it and
+    /// clears it. Returns the number of encounters rated.
+    pub fn rate_pending(&mut self, period: U256) -> Result<usize, VerifierError> {
+        if !self.pending_open.get() || self.pending_period.get() != period {
+            return Err(VerifierError::NoPendingPeriod(NoPendingPeriod { period }));
+        }
+        let mut humans = Vec::with_capacity(self.pending_humans.len());
+        let mut bot_ids = Vec::with_capacity(self.pending_bots.len());
+        let mut guessed_correctly = Vec::with_capacity(self.pending_wins.len());
+        while let Some(human) = self.pending_humans.pop() {
+            humans.push(human);
+            bot_ids.push(self.pending_bots.pop().unwrap_or_default());
+            guessed_correctly.push(self.pending_wins.pop().unwrap_or_default());
+        }
+
+        self.rate(period, &humans, &bot_ids, &guessed_correctly)
+            .ok_or(VerifierError::RatingOverflow(RatingOverflow {}))?;
+        self.pending_open.set(false);
+        self.current_period.set(period);
+        self.rated_any.set(true);
+        Ok(humans.len())
+    }
+
+    pub fn human(&self, human: Address) -> Option<Rating> {
+        self.humans
+            .getter(human)
+            .rating_at(self.current_period.get() + U256::from(1))
+    }
+
+    pub fn bot(&self, bot_id: B256) -> Option<Rating> {
+        self.bots
+            .getter(bot_id)
+            .rating_at(self.current_period.get() + U256::from(1))
+    }
+
+    /// Rates one period from its encounters. `guessed_correctly[i]` is a
+    /// win for `humans[i]` over `bot_ids[i]`. Every participant is updated
+    /// against the others' pre-period ratings, as Glicko-2 requires.
+    /// Returns `None` if any intermediate overflows.
+    fn rate(
+        &mut self,
+        period: U256,
+        humans: &[Address],
+        bot_ids: &[B256],
+        guessed_correctly: &[bool],
+    ) -> Option<()> {
+        let mut human_keys: Vec<Address> = Vec::new();
+        let mut bot_keys: Vec<B256> = Vec::new();
+        let mut human_of: Vec<usize> = Vec::with_capacity(humans.len());
+        let mut bot_of: Vec<usize> = Vec::with_capacity(bot_ids.len());
+        for (human, bot) in humans.iter().zip(bot_ids) {
+            human_of.push(index_of(&mut human_keys, *human));
+            bot_of.push(index_of(&mut bot_keys, *bot));
+        }
+
+        let human_before = human_keys
+            .iter()
+            .map(|h| self.humans.getter(*h).rating_at(period))
+            .collect::<Option<Vec<_>>>()?;
+        let bot_before = bot_keys
+            .iter()
+            .map(|b| self.bots.getter(*b).rating_at(period))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut human_games: Vec<Vec<(Rating, bool)>> = vec![Vec::new(); human_keys.len()];
+        let mut bot_games: Vec<Vec<(Rating, bool)>> = vec![Vec::new(); bot_keys.len()];
+        for (m, won) in guessed_correctly.iter().enumerate() {
+            human_games[human_of[m]].push((bot_before[bot_of[m]], *won));
+            bot_games[bot_of[m]].push((human_before[human_of[m]], !*won));
+        }
+
+        for (i, key) in human_keys.iter().enumerate() {
+            let updated = update(human_before[i], &human_games[i])?;
+            self.humans.setter(*key).set_rating(updated, period);
+        }
+        for (i, key) in bot_keys.iter().enumerate() {
+            let updated = update(bot_before[i], &bot_games[i])?;
+            self.bots.setter(*key).set_rating(updated, period);
+        }
+        Some(())
+    }
+}
+
+fn index_of<T: PartialEq + Copy>(keys: &mut Vec<T>, key: T) -> usize {
+    match keys.iter().position(|k| *k == key) {
+        Some(i) => i,
+        None => {
+            keys.push(key);
+            keys.len() - 1
+        }
+    }
+}
+
+fn sqrt(x: I256) -> Option<I256> {
+    if x.is_negative() {
+        return None;
+    }
+    I256::try_from(math::sqrt_wad(x.into_raw())).ok()
+}
+
+fn exp(x: I256) -> Option<I256> {
+    I256::try_from(math::exp_wad(x)?).ok()
+}
+
+/// g(φ) = 1 / √(1 + 3φ²/π²)
+fn g(phi: I256) -> Option<I256> {
+    let inner = one().checked_add(mul(w(THREE_OVER_PI_SQUARED), mul(phi, phi)?)?)?;
+    div(one(), sqrt(inner)?)
+}
+
+/// E(μ, μj, φj) = 1 / (1 + exp(−g(φj)(μ − μj)))
+fn expected(mu: I256, mu_j: I256, g_j: I256) -> Option<I256> {
+    let exponent = -mul(g_j, mu.checked_sub(mu_j)?)?;
+    div(one(), one().checked_add(exp(exponent)?)?)
+}
+
+/// One player's Glicko-2 update from `games` against pre-period opponents.
+fn update(player: Rating, games: &[(Rating, bool)]) -> Option<Rating> {
+    if games.is_empty() {
+        return player.idle(U256::from(1));
+    }
+
+    // Steps 3–4: estimated variance v and improvement Δ.
+    let mut inverse_v = I256::ZERO;
+    let mut score_sum = I256::ZERO;
+    for (opponent, won) in games {
+        let g_j = g(opponent.phi)?;
+        let e = expected(player.mu, opponent.mu, g_j)?;
+        let score = if *won { one() } else { I256::ZERO };
+        inverse_v = inverse_v.checked_add(mul(mul(g_j, g_j)?, mul(e, one() - e)?)?)?;
+        score_sum = score_sum.checked_add(mul(g_j, score - e)?)?;
+    }
+    if inverse_v.is_zero() {
+        return player.idle(U256::from(1));
+    }
+    let v = div(one(), inverse_v)?;
+    let delta = mul(v, score_sum)?;
+
+    // Step 5: new volatility.
+    let sigma = new_volatility(player, v, delta)?;
+
+    // Steps 6–7: new RD and rating.
+    let phi_star = sqrt(mul(player.phi, player.phi)?.checked_add(mul(sigma, sigma)?)?)?;
+    let inverse_phi2 = div(one(), mul(phi_star, phi_star)?)?.checked_add(inverse_v)?;
+    let phi = div(one(), sqrt(inverse_phi2)?)?;
+    let mu = player.mu.checked_add(mul(mul(phi, phi)?, score_sum)?)?;
+
+    Some(Rating { mu, phi, sigma })
+}
+
+/// Step 5 of Glicko-2: solves for σ' with the Illinois algorithm.
+fn new_volatility(player: Rating, v: I256, delta: I256) -> Option<I256> {
+    let tau = w(TAU);
+    let tau2 = mul(tau, tau)?;
+    let phi2 = mul(player.phi, player.phi)?;
+    let delta2 = mul(delta, delta)?;
+    let a = math::ln_wad(mul(player.sigma, player.sigma)?.into_raw())?;
+
+    let f = |x: I256| -> Option<I256> {
+        let ex = exp(x)?;
+        let denom = phi2.checked_add(v)?.checked_add(ex)?;
+        let num = mul(ex, delta2 - phi2 - v - ex)?;
+        let lhs = div(num, mul(w(2) * one(), mul(denom, denom)?)?)?;
+        lhs.checked_sub(div(x - a, tau2)?)
+    };
+
+    let mut big_a = a;
+    let mut big_b = if delta2 > phi2.checked_add(v)? {
+        math::ln_wad((delta2 - phi2 - v).into_raw())?
+    } else {
+        let mut k = 1i128;
+        while f(a - mul(w(k) * one(), tau)?)?.is_negative() {
+            k += 1;
+            if k as usize > MAX_ITERATIONS {
+                return None;
+            }
+        }
+        a - mul(w(k) * one(), tau)?
+    };
+
+    let mut f_a = f(big_a)?;
+    let mut f_b = f(big_b)?;
+    let mut iterations = 0;
+    while (big_b - big_a).abs() > w(EPSILON) && iterations < MAX_ITERATIONS {
+        let c = big_a.checked_add(div(mul(big_a - big_b, f_a)?, f_b - f_a)?)?;
+        let f_c = f(c)?;
+        if f_c.is_zero() || f_b.is_zero() || f_c.is_negative() != f_b.is_negative() {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= w(2);
+        }
+        big_b = c;
+        f_b = f_c;
+        iterations += 1;
+    }
+
+    exp(big_a / w(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, I256, U256};
+
+    use super::*;
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+    const BOT: B256 = B256::repeat_byte(0xb0);
+
+    fn add(contract: &mut Glicko2, period: u64, count: usize) -> Result<usize, VerifierError> {
+        contract.add_encounters(
+            U256::from(period),
+            &vec![ALICE; count],
+            &vec![BOT; count],
+            &vec![true; count],
+        )
+    }
+
+    /// Glicko-1 `(rating, rd)` to a Glicko-2 rating with σ = 0.06.
+    fn rating(r: i128, rd: i128) -> Rating {
+        let wad = WAD.to::<i128>();
+        Rating {
+            mu: div(w((r - 1_500) * wad), w(SCALE)).unwrap(),
+            phi: div(w(rd * wad), w(SCALE)).unwrap(),
+            sigma: w(INITIAL_VOLATILITY),
+        }
+    }
+
+    fn assert_near(actual: I256, expected: f64, tolerance: f64) {
+        let actual = actual.to_string().parse::<f64>().unwrap() / 1e18;
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[motsu::test]
+    fn rates_period_zero_first(contract: Glicko2) {
+        assert!(!contract.rated_any());
+        assert!(matches!(add(contract, 0, 1), Ok(1)));
+        assert!(matches!(contract.rate_pending(U256::ZERO), Ok(1)));
+        assert!(contract.rated_any());
+        assert_eq!(contract.current_period(), U256::ZERO);
+
+        assert!(matches!(
+            add(contract, 0, 1),
+            Err(VerifierError::StalePeriod(_))
+        ));
+        assert!(matches!(add(contract, 1, 1), Ok(1)));
+    }
+
+    #[motsu::test]
+    fn accumulates_a_period_over_several_calls(contract: Glicko2) {
+        assert!(matches!(add(contract, 7, MAX_PERIOD_ENCOUNTERS), Ok(256)));
+        assert!(matches!(add(contract, 7, MAX_PERIOD_ENCOUNTERS), Ok(512)));
+        assert_eq!(contract.pending(), (true, U256::from(7), U256::from(512)));
+        assert!(!contract.rated_any());
+
+        assert!(matches!(contract.rate_pending(U256::from(7)), Ok(512)));
+        assert_eq!(contract.pending(), (false, U256::from(7), U256::ZERO));
+        assert_eq!(contract.current_period(), U256::from(7));
+        assert!(matches!(
+            contract.rate_pending(U256::from(7)),
+            Err(VerifierError::NoPendingPeriod(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn split_period_rates_like_a_single_call(contract: Glicko2) {
+        let humans = [ALICE, BOB, ALICE];
+        let bots = [BOT, BOT, B256::repeat_byte(0xb1)];
+        let wins = [true, false, false];
+        assert!(contract
+            .add_encounters(U256::from(1), &humans[..1], &bots[..1], &wins[..1])
+            .is_ok());
+        assert!(contract
+            .add_encounters(U256::from(1), &humans[1..], &bots[1..], &wins[1..])
+            .is_ok());
+        assert!(contract.rate_pending(U256::from(1)).is_ok());
+
+        // Same encounters in one go, on a second tracker.
+        let mut single = unsafe { Glicko2::new(U256::from(1) << 128, 0) };
+        assert!(single
+            .add_encounters(U256::from(1), &humans, &bots, &wins)
+            .is_ok());
+        assert!(single.rate_pending(U256::from(1)).is_ok());
+
+        for (split, whole) in [
+            (contract.human(ALICE), single.human(ALICE)),
+            (contract.human(BOB), single.human(BOB)),
+            (contract.bot(BOT), single.bot(BOT)),
+        ] {
+            let (split, whole) = (split.unwrap(), whole.unwrap());
+            assert_eq!(
+                (split.mu, split.phi, split.sigma),
+                (whole.mu, whole.phi, whole.sigma)
+            );
+        }
+    }
+
+    #[motsu::test]
+    fn one_period_accumulates_at_a_time(contract: Glicko2) {
+        assert!(add(contract, 3, 1).is_ok());
+        assert!(matches!(
+            add(contract, 4, 1),
+            Err(VerifierError::PeriodInProgress(PeriodInProgress { period })) if period == U256::from(3)
+        ));
+        assert!(matches!(
+            contract.rate_pending(U256::from(4)),
+            Err(VerifierError::NoPendingPeriod(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn bounds_encounters_per_call_and_period(contract: Glicko2) {
+        assert!(matches!(
+            add(contract, 1, MAX_PERIOD_ENCOUNTERS + 1),
+            Err(VerifierError::TooManyEncounters(_))
+        ));
+        assert!(matches!(
+            contract.add_encounters(U256::from(1), &[ALICE], &[BOT, BOT], &[true]),
+            Err(VerifierError::ArrayLengthMismatch(_))
+        ));
+        for _ in 0..MAX_PENDING_ENCOUNTERS / MAX_PERIOD_ENCOUNTERS {
+            assert!(add(contract, 1, MAX_PERIOD_ENCOUNTERS).is_ok());
+        }
+        assert!(matches!(
+            add(contract, 1, 1),
+            Err(VerifierError::TooManyEncounters(TooManyEncounters { max, .. }))
+                if max == U256::from(MAX_PENDING_ENCOUNTERS)
+        ));
+    }
+
+    #[motsu::test]
+    fn matches_glickmans_worked_example(contract: Glicko2) {
+        // Glickman (2013): a 1500/200 player beats a 1400/30 player and loses
+        // to 1550/100 and 1700/300, ending at 1464.06 / 151.52 / 0.05999.
+        let opponents = [
+            (B256::repeat_byte(1), rating(1_400, 30)),
+            (B256::repeat_byte(2), rating(1_550, 100)),
+            (B256::repeat_byte(3), rating(1_700, 300)),
+        ];
+        contract
+            .humans
+            .setter(ALICE)
+            .set_rating(rating(1_500, 200), U256::ZERO);
+        for (bot, rating) in opponents {
+            contract.bots.setter(bot).set_rating(rating, U256::ZERO);
+        }
+        let bots = opponents.map(|(bot, _)| bot);
+        assert!(contract
+            .add_encounters(U256::from(1), &[ALICE; 3], &bots, &[true, false, false])
+            .is_ok());
+        assert!(contract.rate_pending(U256::from(1)).is_ok());
+
+        let (r, rd, sigma, _) = contract.human(ALICE).unwrap().to_public().unwrap();
+        assert_near(r, 1_464.06, 0.01);
+        assert_near(rd.try_into().unwrap(), 151.52, 0.01);
+        assert_near(sigma.try_into().unwrap(), 0.05999, 0.00001);
+    }
+}
@@ -0,0 +1,215 @@
+//! Pull-payment ledger: amounts each wallet can withdraw, per token
+//! (`address(0)` for native currency). Mirrors V4's `pendingWithdrawals`,
+//! plus what V4 leaves to the admin: a running `total_pending[token]`, kept
+//! equal to the sum of every wallet's pending balance in that token, and
+//! `total_escrowed[token]` for funds held but not yet owed to anyone (stakes
+//! on unsettled matches, bonds on unresolved disputes). The house can only
+//! ever withdraw what is in neither.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageU256},
+};
+
+use crate::errors::*;
+use crate::events::HouseWalletUpdated;
+
+#[storage]
+pub struct PayoutLedger {
+    /// wallet => token => withdrawable amount.
+    pending: StorageMap<Address, StorageMap<Address, StorageU256>>,
+    /// token => sum of `pending[*][token]`.
+    total_pending: StorageMap<Address, StorageU256>,
+    /// token => stakes and bonds awaiting a match result.
+    total_escrowed: StorageMap<Address, StorageU256>,
+    /// Receives house withdrawals.
+    house_wallet: StorageAddress,
+    /// Reentrancy guard around outgoing transfers.
+    locked: StorageBool,
+}
+
+impl PayoutLedger {
+    pub fn credit(&mut self, wallet: Address, token: Address, amount: U256) {
+        let mut by_token = self.pending.setter(wallet);
+        let mut pending = by_token.setter(token);
+        let balance = pending.get();
+        pending.set(balance + amount);
+
+        let mut total = self.total_pending.setter(token);
+        let sum = total.get();
+        total.set(sum + amount);
+    }
+
+    /// Holds `amount` of `token` in escrow until `release`.
+    pub fn hold(&mut self, token: Address, amount: U256) {
+        let mut total = self.total_escrowed.setter(token);
+        let sum = total.get();
+        total.set(sum + amount);
+    }
+
+    /// Takes `amount` of `token` out of escrow, once it has been credited to
+    /// wallets or left to the house.
+    pub fn release(&mut self, token: Address, amount: U256) {
+        let mut total = self.total_escrowed.setter(token);
+        let sum = total.get();
+        total.set(sum - amount);
+    }
+
+    /// Zeroes `wallet`'s balance in `token` and returns it, ahead of paying
+    /// it out.
+    pub fn take(&mut self, wallet: Address, token: Address) -> Result<U256, VerifierError> {
+        let mut by_token = self.pending.setter(wallet);
+        let mut pending = by_token.setter(token);
+        let amount = pending.get();
+        if amount == U256::ZERO {
+            return Err(VerifierError::NoFundsToWithdraw(NoFundsToWithdraw {}));
+        }
+        pending.set(U256::ZERO);
+
+        let mut total = self.total_pending.setter(token);
+        let sum = total.get();
+        total.set(sum - amount);
+        Ok(amount)
+    }
+
+    pub fn pending_of(&self, wallet: Address, token: Address) -> U256 {
+        self.pending.getter(wallet).get(token)
+    }
+
+    pub fn total_pending(&self, token: Address) -> U256 {
+        self.total_pending.get(token)
+    }
+
+    pub fn total_escrowed(&self, token: Address) -> U256 {
+        self.total_escrowed.get(token)
+    }
+
+    /// Part of a `balance` of `token` neither backing a pending claim nor
+    /// held in escrow.
+    pub fn house_available(&self, token: Address, balance: U256) -> U256 {
+        balance
+            .saturating_sub(self.total_pending.get(token))
+            .saturating_sub(self.total_escrowed.get(token))
+    }
+
+    pub fn house_wallet(&self) -> Address {
+        self.house_wallet.get()
+    }
+
+    pub fn set_house_wallet(&mut self, wallet: Address) -> Result<(), VerifierError> {
+        if wallet == Address::ZERO {
+            return Err(VerifierError::InvalidAddress(InvalidAddress {}));
+        }
+        self.house_wallet.set(wallet);
+        evm::log(HouseWalletUpdated {
+            houseWallet: wallet,
+        });
+        Ok(())
+    }
+
+    pub fn lock(&mut self) -> Result<(), VerifierError> {
+        if self.locked.get() {
+            return Err(VerifierError::ReentrantCall(ReentrantCall {}));
+        }
+        self.locked.set(true);
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, U256};
+
+    use super::*;
+    use crate::staking::{NATIVE, USDC};
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+
+    #[motsu::test]
+    fn total_pending_tracks_every_balance(contract: PayoutLedger) {
+        contract.credit(ALICE, NATIVE, U256::from(300));
+        contract.credit(BOB, NATIVE, U256::from(200));
+        contract.credit(ALICE, NATIVE, U256::from(50));
+        contract.credit(ALICE, USDC, U256::from(7));
+        assert_eq!(contract.pending_of(ALICE, NATIVE), U256::from(350));
+        assert_eq!(contract.total_pending(NATIVE), U256::from(550));
+        assert_eq!(contract.total_pending(USDC), U256::from(7));
+
+        assert!(matches!(contract.take(ALICE, NATIVE), Ok(amount) if amount == U256::from(350)));
+        assert_eq!(contract.pending_of(ALICE, NATIVE), U256::ZERO);
+        assert_eq!(contract.total_pending(NATIVE), U256::from(200));
+        assert_eq!(contract.pending_of(ALICE, USDC), U256::from(7));
+    }
+
+    #[motsu::test]
+    fn take_rejects_an_empty_balance(contract: PayoutLedger) {
+        assert!(matches!(
+            contract.take(ALICE, NATIVE),
+            Err(VerifierError::NoFundsToWithdraw(_))
+        ));
+        contract.credit(ALICE, NATIVE, U256::from(1));
+        assert!(contract.take(ALICE, NATIVE).is_ok());
+        assert!(matches!(
+            contract.take(ALICE, NATIVE),
+            Err(VerifierError::NoFundsToWithdraw(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn house_gets_neither_pending_nor_escrowed_funds(contract: PayoutLedger) {
+        contract.credit(ALICE, NATIVE, U256::from(100));
+        contract.hold(NATIVE, U256::from(400));
+        assert_eq!(contract.total_escrowed(NATIVE), U256::from(400));
+        assert_eq!(
+            contract.house_available(NATIVE, U256::from(1_000)),
+            U256::from(500)
+        );
+
+        // Settling moves escrow to a claim: still not the house's.
+        contract.release(NATIVE, U256::from(400));
+        contract.credit(BOB, NATIVE, U256::from(350));
+        assert_eq!(contract.total_escrowed(NATIVE), U256::ZERO);
+        assert_eq!(
+            contract.house_available(NATIVE, U256::from(1_000)),
+            U256::from(550)
+        );
+
+        // Never more than the balance, even if it falls short.
+        assert_eq!(
+            contract.house_available(NATIVE, U256::from(300)),
+            U256::ZERO
+        );
+        assert_eq!(
+            contract.house_available(USDC, U256::from(300)),
+            U256::from(300)
+        );
+    }
+
+    #[motsu::test]
+    fn rejects_a_zero_house_wallet(contract: PayoutLedger) {
+        assert!(matches!(
+            contract.set_house_wallet(Address::ZERO),
+            Err(VerifierError::InvalidAddress(_))
+        ));
+        assert!(contract.set_house_wallet(BOB).is_ok());
+        assert_eq!(contract.house_wallet(), BOB);
+    }
+
+    #[motsu::test]
+    fn lock_rejects_reentry(contract: PayoutLedger) {
+        assert!(contract.lock().is_ok());
+        assert!(matches!(
+            contract.lock(),
+            Err(VerifierError::ReentrantCall(_))
+        ));
+        contract.unlock();
+        assert!(contract.lock().is_ok());
+    }
+}
@@ -0,0 +1,1730 @@
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+mod access;
+mod attestation;
+mod deception;
+mod disputes;
+mod elo;
+mod entry;
+mod errors;
+mod events;
+mod glicko;
+mod ledger;
+mod merkle;
+mod models;
+mod personas;
+mod policy;
+mod registry;
+mod staking;
+mod timelock;
+mod voting;
+
+/// Import items from the SDK. The core of writing Stylus contracts is the `stylus_sdk` crate.
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{hex, Address, B256, I256, U256},
+    block,
+    call::{self, Call},
+    contract, evm, msg,
+    prelude::*,
+    storage::{StorageAddress, StorageB256, StorageBool, StorageMap, StorageU256},
+};
+
+use detective_scoring::confidence;
+use detective_scoring::stats::{self, LatencyStats};
+
+use crate::access::{
+    AccessControl, ARBITER_ROLE, DEFAULT_ADMIN_ROLE, MANAGEMENT_ROLES, PAUSER_ROLE,
+    RESULT_ORACLE_ROLE, THRESHOLD_MANAGER_ROLE, TREASURER_ROLE,
+};
+use crate::attestation::PerformanceAttestation;
+use crate::deception::DeceptionPrior;
+use crate::disputes::OptimisticResults;
+use crate::elo::EloRatings;
+use crate::entry::GameEntry;
+use crate::errors::*;
+use crate::events::{
+    AdminTransferProposed, AdminTransferred, DeceptionRated, EloUpdated, GlickoEncountersAdded,
+    GlickoPeriodRated, HouseFundsWithdrawn, HumanityChecked, KFactorUpdated, ModelMetadataUpdated,
+    ModelOperatorUpdated, ModelOutcomesRecorded, ModelRegistered, PersonaOutcomesRecorded,
+    Withdrawal,
+};
+use crate::glicko::Glicko2;
+use crate::ledger::PayoutLedger;
+use crate::models::ModelRegistry;
+use crate::personas::PersonaStats;
+use crate::policy::HumanityPolicy;
+use crate::registry::{HumanityRegistry, VerificationMethod};
+use crate::staking::{StakeBook, IERC20, NATIVE, USDC};
+use crate::timelock::{ChangeKind, Timelock};
+use crate::voting::CommitRevealVoting;
+
+/// Only account allowed to call `initialize`, baked in at build time from
+/// `DETECTIVE_DEPLOYER` (hex). Without it, `initialize` always reverts, so a
+/// build that forgot the variable cannot be claimed by whoever calls first.
+const DEPLOYER: Address = match option_env!("DETECTIVE_DEPLOYER") {
+    Some(deployer) => match hex::const_decode_to_array::<20>(deployer.as_bytes()) {
+        Ok(bytes) => Address::new(bytes),
+        Err(_) => panic!("DETECTIVE_DEPLOYER must be a 20-byte hex address"),
+    },
+    None => Address::ZERO,
+};
+
+/// Entries per batch call; one bit each in `verify_humanity_batch`'s bitmap.
+const MAX_BATCH_SIZE: usize = 256;
+
+#[storage]
+#[entrypoint]
+pub struct DetectiveStylusVerifier {
+    /// Account given every management role at setup; non-zero once initialized.
+    admin: StorageAddress,
+    /// Key the game server uses to sign performance attestations.
+    game_server_signer: StorageAddress,
+    /// wallet => nonce => consumed. Prevents replaying an attestation.
+    used_nonces: StorageMap<Address, StorageMap<U256, StorageBool>>,
+    /// cycle id => Merkle root of that cycle's player results.
+    cycle_roots: StorageMap<U256, StorageB256>,
+    /// cycle id => timestamp the root was posted; anchors proof-based expiry.
+    cycle_posted_at: StorageMap<U256, StorageU256>,
+    /// Wallets that passed the humanity rule, and until when.
+    registry: HumanityRegistry,
+    /// Active humanity thresholds.
+    policy: HumanityPolicy,
+    /// Beta prior smoothing AI deception ratings.
+    deception_prior: DeceptionPrior,
+    /// Skill ratings for human detectives and AI bots.
+    elo: EloRatings,
+    /// Glicko-2 ratings, one rating period per game cycle.
+    glicko: Glicko2,
+    /// AI models benchmarked by how often they fool humans.
+    models: ModelRegistry,
+    /// Impersonation outcomes per Farcaster FID.
+    personas: PersonaStats,
+    /// Commit–reveal votes and the per-player records graded from them.
+    voting: CommitRevealVoting,
+    /// Game registration, pause switch and entry fee.
+    entry: GameEntry,
+    /// Native and USDC stakes per match.
+    stakes: StakeBook,
+    /// Winnings and refunds awaiting withdrawal.
+    ledger: PayoutLedger,
+    /// Proposed, disputed and final match results.
+    results: OptimisticResults,
+    /// Role assignments.
+    access: AccessControl,
+    /// Admin nominated by `propose_admin`, pending acceptance.
+    pending_admin: StorageAddress,
+    /// Queue of scheduled parameter changes.
+    timelock: Timelock,
+}
+
+/// Define the implementation of the contract.
+#[public]
+impl DetectiveStylusVerifier {
+    /// One-time setup, since Stylus programs have no constructor. Only the
+    /// build's `DEPLOYER` may call it, so nobody can front-run the call
+    /// between deployment and initialization.
+    pub fn initialize(&mut self, admin: Address, signer: Address) -> Result<(), VerifierError> {
+        if msg::sender() != DEPLOYER {
+            return Err(VerifierError::NotDeployer(NotDeployer {
+                caller: msg::sender(),
+            }));
+        }
+        if self.admin.get() != Address::ZERO {
+            return Err(VerifierError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if admin == Address::ZERO || signer == Address::ZERO {
+            return Err(VerifierError::InvalidAddress(InvalidAddress {}));
+        }
+        self.admin.set(admin);
+        self.game_server_signer.set(signer);
+        self.registry
+            .set_period(U256::from(registry::DEFAULT_VERIFICATION_PERIOD));
+        self.policy.set_defaults()?;
+        self.deception_prior.set_defaults()?;
+        self.elo.set_k_factor(U256::from(elo::DEFAULT_K_FACTOR));
+        self.ledger.set_house_wallet(admin)?;
+        self.results.set_defaults();
+        self.timelock
+            .set_delay(U256::from(timelock::DEFAULT_DELAY))?;
+        self.access.grant(DEFAULT_ADMIN_ROLE, admin, msg::sender());
+        for role in MANAGEMENT_ROLES {
+            self.access.grant(role, admin, msg::sender());
+        }
+        Ok(())
+    }
+
+    /// Nominates `new_admin` to take over `DEFAULT_ADMIN_ROLE`; takes effect
+    /// once they call `accept_admin`. Proposing the zero address cancels.
+    pub fn propose_admin(&mut self, new_admin: Address) -> Result<(), VerifierError> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.pending_admin.set(new_admin);
+        evm::log(AdminTransferProposed {
+            currentAdmin: self.admin.get(),
+            pendingAdmin: new_admin,
+        });
+        Ok(())
+    }
+
+    /// Completes a handover started by `propose_admin`: the caller receives
+    /// `DEFAULT_ADMIN_ROLE` and the management roles, and the previous admin
+    /// loses all of them.
+    pub fn accept_admin(&mut self) -> Result<(), VerifierError> {
+        let new_admin = msg::sender();
+        if new_admin != self.pending_admin.get() || new_admin == Address::ZERO {
+            return Err(VerifierError::NotPendingAdmin(NotPendingAdmin {}));
+        }
+        let old_admin = self.admin.get();
+        for role in [DEFAULT_ADMIN_ROLE].into_iter().chain(MANAGEMENT_ROLES) {
+            if old_admin != new_admin {
+                self.access.revoke(role, old_admin, new_admin);
+            }
+            self.access.grant(role, new_admin, new_admin);
+        }
+        self.admin.set(new_admin);
+        self.pending_admin.set(Address::ZERO);
+        evm::log(AdminTransferred {
+            oldAdmin: old_admin,
+            newAdmin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn pending_admin(&self) -> Address {
+        self.pending_admin.get()
+    }
+
+    /// Queues a parameter change (see `ChangeKind` for kinds and their
+    /// ABI-encoded params), executable after the timelock delay. Returns its id.
+    pub fn schedule_change(&mut self, kind: u8, params: Bytes) -> Result<B256, VerifierError> {
+        self.only_role(ChangeKind::from_u8(kind)?.role())?;
+        self.timelock
+            .schedule(kind, &params, U256::from(block::timestamp()))
+    }
+
+    pub fn cancel_change(&mut self, kind: u8, params: Bytes) -> Result<(), VerifierError> {
+        self.only_role(ChangeKind::from_u8(kind)?.role())?;
+        self.timelock.cancel(kind, &params)
+    }
+
+    /// Applies a queued change whose ETA has passed.
+    pub fn execute_change(&mut self, kind: u8, params: Bytes) -> Result<(), VerifierError> {
+        let change = ChangeKind::from_u8(kind)?;
+        self.only_role(change.role())?;
+        self.timelock
+            .consume(kind, &params, U256::from(block::timestamp()))?;
+        self.apply_change(change, &params)
+    }
+
+    /// ETA of a queued change; zero if not queued.
+    pub fn change_eta(&self, kind: u8, params: Bytes) -> U256 {
+        self.timelock.eta(timelock::change_id(kind, &params))
+    }
+
+    pub fn timelock_delay(&self) -> U256 {
+        self.timelock.delay()
+    }
+
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.access.has_role(role, account)
+    }
+
+    pub fn get_role_admin(&self, role: B256) -> B256 {
+        self.access.role_admin(role)
+    }
+
+    /// Grants `role` to `account`. Caller must hold the role's admin role.
+    /// `DEFAULT_ADMIN_ROLE` can only be handed over with `propose_admin`.
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), VerifierError> {
+        access::ensure_not_default_admin(role)?;
+        self.only_role(self.access.role_admin(role))?;
+        self.access.grant(role, account, msg::sender());
+        Ok(())
+    }
+
+    /// Revokes `role` from `account`. Caller must hold the role's admin role.
+    /// `DEFAULT_ADMIN_ROLE` can only be handed over with `propose_admin`.
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), VerifierError> {
+        access::ensure_not_default_admin(role)?;
+        self.only_role(self.access.role_admin(role))?;
+        self.access.revoke(role, account, msg::sender());
+        Ok(())
+    }
+
+    /// Gives up one of the caller's own roles. `caller_confirmation` must be
+    /// the caller's address, as in OpenZeppelin 5. `DEFAULT_ADMIN_ROLE`
+    /// cannot be renounced, only handed over.
+    pub fn renounce_role(
+        &mut self,
+        role: B256,
+        caller_confirmation: Address,
+    ) -> Result<(), VerifierError> {
+        access::ensure_not_default_admin(role)?;
+        if caller_confirmation != msg::sender() {
+            return Err(VerifierError::AccessControlBadConfirmation(
+                AccessControlBadConfirmation {},
+            ));
+        }
+        self.access.revoke(role, caller_confirmation, msg::sender());
+        Ok(())
+    }
+
+    /// Makes `admin_role` the role that administers `role`, which cannot be
+    /// `DEFAULT_ADMIN_ROLE`.
+    pub fn set_role_admin(&mut self, role: B256, admin_role: B256) -> Result<(), VerifierError> {
+        access::ensure_not_default_admin(role)?;
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.access.set_role_admin(role, admin_role);
+        Ok(())
+    }
+
+    #[selector(name = "DEFAULT_ADMIN_ROLE")]
+    pub fn default_admin_role(&self) -> B256 {
+        DEFAULT_ADMIN_ROLE
+    }
+
+    #[selector(name = "RESULT_ORACLE_ROLE")]
+    pub fn result_oracle_role(&self) -> B256 {
+        RESULT_ORACLE_ROLE
+    }
+
+    #[selector(name = "PAUSER_ROLE")]
+    pub fn pauser_role(&self) -> B256 {
+        PAUSER_ROLE
+    }
+
+    #[selector(name = "TREASURER_ROLE")]
+    pub fn treasurer_role(&self) -> B256 {
+        TREASURER_ROLE
+    }
+
+    #[selector(name = "THRESHOLD_MANAGER_ROLE")]
+    pub fn threshold_manager_role(&self) -> B256 {
+        THRESHOLD_MANAGER_ROLE
+    }
+
+    #[selector(name = "ARBITER_ROLE")]
+    pub fn arbiter_role(&self) -> B256 {
+        ARBITER_ROLE
+    }
+
+    /// Active policy as `(minAccuracyPct, minLatencyMs, maxLatencyMs, version)`.
+    pub fn humanity_thresholds(&self) -> (U256, U256, U256, U256) {
+        self.policy.get()
+    }
+
+    /// Returns `(minLatencyCvBps, minSingleLatencyMs)`.
+    pub fn distribution_thresholds(&self) -> (U256, U256) {
+        self.policy.distribution()
+    }
+
+    /// Returns `(wilsonZWad, minMatches, minAccuracyLowerBoundBps)`.
+    pub fn confidence_thresholds(&self) -> (U256, U256, U256) {
+        self.policy.confidence()
+    }
+
+    /// Wilson score lower bound on accuracy, in basis points, under the
+    /// active `z`. Zero for empty or inconsistent input.
+    pub fn accuracy_lower_bound_bps(&self, correct_guesses: U256, total_matches: U256) -> U256 {
+        confidence::wilson_lower_bound_bps(
+            correct_guesses,
+            total_matches,
+            self.policy.wilson_z_wad(),
+        )
+        .unwrap_or(U256::ZERO)
+    }
+
+    pub fn admin(&self) -> Address {
+        self.admin.get()
+    }
+
+    pub fn game_server_signer(&self) -> Address {
+        self.game_server_signer.get()
+    }
+
+    pub fn is_nonce_used(&self, wallet: Address, nonce: U256) -> bool {
+        self.used_nonces.getter(wallet).get(nonce)
+    }
+
+    /// Anchors one Merkle root per game cycle. Roots are write-once so a
+    /// published result set cannot be swapped out after players prove against it.
+    pub fn post_cycle_root(&mut self, cycle_id: U256, root: B256) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        if root == B256::ZERO {
+            return Err(VerifierError::InvalidMerkleRoot(InvalidMerkleRoot {}));
+        }
+        if self.cycle_roots.get(cycle_id) != B256::ZERO {
+            return Err(VerifierError::CycleRootAlreadySet(CycleRootAlreadySet {
+                cycleId: cycle_id,
+            }));
+        }
+        self.cycle_roots.setter(cycle_id).set(root);
+        self.cycle_posted_at
+            .setter(cycle_id)
+            .set(U256::from(block::timestamp()));
+        Ok(())
+    }
+
+    pub fn cycle_root(&self, cycle_id: U256) -> B256 {
+        self.cycle_roots.get(cycle_id)
+    }
+
+    pub fn verification_period(&self) -> U256 {
+        self.registry.period()
+    }
+
+    /// Whether `wallet` holds an unexpired humanity verification.
+    pub fn is_verified_human(&self, wallet: Address) -> bool {
+        self.registry
+            .is_verified(wallet, U256::from(block::timestamp()))
+    }
+
+    /// Returns `(verifiedUntil, method)` for `wallet`; zero if never verified.
+    pub fn humanity_record(&self, wallet: Address) -> (U256, u8) {
+        (
+            self.registry.verified_until(wallet),
+            self.registry.method(wallet),
+        )
+    }
+
+    /// Clears a lapsed verification and emits `HumanityExpired`. Callable by
+    /// anyone so indexers see expiries without waiting for the player.
+    pub fn expire_humanity(&mut self, wallet: Address) -> bool {
+        self.registry.expire(wallet, U256::from(block::timestamp()))
+    }
+
+    /// EIP-712 domain separator the game server signs against.
+    pub fn domain_separator(&self) -> B256 {
+        attestation::domain().separator()
+    }
+
+    /// Verifies if a user's game performance meets the "Humanity Threshold".
+    /// This logic is written in Rust to demonstrate high-efficiency computation
+    /// that would be more expensive in Solidity.
+    pub fn verify_humanity_score(
+        &self,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+    ) -> bool {
+        // Logic (thresholds change via timelocked `HumanityThresholds`):
+        // 1. Accuracy must be > 60% (Humans are better than random at detecting bots)
+        // 2. Response time must not be "too fast" (e.g., < 500ms suggests a bot script)
+        // 3. Response time must not be "too slow" (e.g., > 240,000ms suggests abandonment)
+        // 4. Enough matches that the Wilson lower bound on accuracy still beats
+        //    chance (see `ConfidenceThresholds`)
+        self.policy
+            .verdict(correct_guesses, total_matches, avg_response_time_ms)
+            .passed()
+    }
+
+    /// Why `verify_humanity_score` passes or fails, as a reason code:
+    /// 0 human, 1 inconsistent input (e.g. correct > total), 2 insufficient
+    /// matches, 3 accuracy too low, 4 responses too fast, 5 responses too
+    /// slow, 6 accuracy not significant over the sample.
+    pub fn humanity_verdict(
+        &self,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+    ) -> u8 {
+        self.policy
+            .verdict(correct_guesses, total_matches, avg_response_time_ms)
+            .code()
+    }
+
+    /// Reverting form of `verify_humanity_score`: fails with a custom error
+    /// naming the check that did not pass.
+    pub fn require_human(
+        &self,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+    ) -> Result<(), VerifierError> {
+        policy::require(
+            self.policy
+                .verdict(correct_guesses, total_matches, avg_response_time_ms),
+        )
+    }
+
+    /// Returns `(mean, variance, cvBps, median, min)` of per-match latencies.
+    pub fn latency_stats(
+        &self,
+        latencies_ms: Vec<U256>,
+    ) -> Result<(U256, U256, U256, U256, U256), VerifierError> {
+        let s = Self::compute_latency_stats(&latencies_ms)?;
+        Ok((s.mean, s.variance, s.cv_bps, s.median, s.min))
+    }
+
+    /// Distribution-aware form of `verify_humanity_score`: takes every
+    /// match's latency rather than a caller-computed average. On top of the
+    /// accuracy and mean-latency rule, rejects players whose fastest vote
+    /// beats the human floor or whose timing is implausibly uniform.
+    pub fn verify_latency_distribution(
+        &self,
+        correct_guesses: U256,
+        latencies_ms: Vec<U256>,
+    ) -> Result<bool, VerifierError> {
+        if latencies_ms.is_empty() {
+            return Ok(false);
+        }
+        let stats = Self::compute_latency_stats(&latencies_ms)?;
+        let total_matches = U256::from(latencies_ms.len());
+
+        Ok(
+            self.verify_humanity_score(correct_guesses, total_matches, stats.mean)
+                && self.policy.distribution_ok(&stats, latencies_ms.len()),
+        )
+    }
+
+    /// Same rule as `verify_humanity_score`, but only for numbers the game
+    /// server signed. The nonce is consumed even when the rule fails, so each
+    /// attestation can be used exactly once.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_signed_performance(
+        &mut self,
+        wallet: Address,
+        cycle_id: U256,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+        nonce: U256,
+        expiry: U256,
+        signature: Bytes,
+    ) -> Result<bool, VerifierError> {
+        if U256::from(block::timestamp()) > expiry {
+            return Err(VerifierError::AttestationExpired(AttestationExpired {
+                expiry,
+            }));
+        }
+        if self.used_nonces.getter(wallet).get(nonce) {
+            return Err(VerifierError::NonceAlreadyUsed(NonceAlreadyUsed {
+                wallet,
+                nonce,
+            }));
+        }
+
+        let payload = PerformanceAttestation {
+            wallet,
+            cycleId: cycle_id,
+            correctGuesses: correct_guesses,
+            totalMatches: total_matches,
+            avgResponseTimeMs: avg_response_time_ms,
+            nonce,
+            expiry,
+        };
+        let signer = attestation::recover_signer(attestation::signing_hash(&payload), &signature)
+            .ok_or(VerifierError::InvalidSignature(InvalidSignature {}))?;
+        if signer != self.game_server_signer.get() {
+            return Err(VerifierError::UnauthorizedSigner(UnauthorizedSigner {
+                signer,
+            }));
+        }
+
+        self.used_nonces.setter(wallet).setter(nonce).set(true);
+
+        let passed = self.check_humanity(
+            wallet,
+            VerificationMethod::SignedAttestation as u8,
+            correct_guesses,
+            total_matches,
+            avg_response_time_ms,
+        );
+        if passed {
+            self.registry.record(
+                wallet,
+                VerificationMethod::SignedAttestation,
+                U256::from(block::timestamp()),
+            );
+        }
+        Ok(passed)
+    }
+
+    /// Self-serve verification: proves a player's stats are part of the
+    /// cycle's anchored results, then applies `verify_humanity_score`.
+    /// A pass is registered from the time the cycle root was posted.
+    pub fn prove_humanity(
+        &mut self,
+        cycle_id: U256,
+        wallet: Address,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+        proof: Vec<B256>,
+    ) -> Result<bool, VerifierError> {
+        let root = self.cycle_roots.get(cycle_id);
+        if root == B256::ZERO {
+            return Err(VerifierError::UnknownCycle(UnknownCycle {
+                cycleId: cycle_id,
+            }));
+        }
+
+        let leaf =
+            merkle::player_leaf(wallet, correct_guesses, total_matches, avg_response_time_ms);
+        if !merkle::verify(&proof, root, leaf) {
+            return Err(VerifierError::InvalidMerkleProof(InvalidMerkleProof {}));
+        }
+
+        let passed = self.check_humanity(
+            wallet,
+            VerificationMethod::MerkleProof as u8,
+            correct_guesses,
+            total_matches,
+            avg_response_time_ms,
+        );
+        if passed {
+            let posted_at = self.cycle_posted_at.get(cycle_id);
+            self.registry
+                .record(wallet, VerificationMethod::MerkleProof, posted_at);
+        }
+        Ok(passed)
+    }
+
+    /// Computes a "Deception Rating" for an AI agent.
+    /// Higher rating means the bot is better at fooling humans.
+    pub fn calculate_deception_rating(
+        &self,
+        times_fooled_human: U256,
+        total_interactions: U256,
+    ) -> U256 {
+        // Return percentage 0-100
+        detective_scoring::deception::rating_pct(times_fooled_human, total_interactions)
+    }
+
+    /// Logged form of `verify_humanity_score` for caller-supplied numbers:
+    /// emits `HumanityChecked` with method `SELF_REPORTED` and the caller as
+    /// reporter, so indexers can tell it from proven checks. Registers
+    /// nothing.
+    pub fn record_humanity_check(
+        &mut self,
+        wallet: Address,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+    ) -> bool {
+        self.check_humanity(
+            wallet,
+            registry::SELF_REPORTED,
+            correct_guesses,
+            total_matches,
+            avg_response_time_ms,
+        )
+    }
+
+    /// Logged form of `calculate_deception_rating`: emits `DeceptionRated`,
+    /// marked `SELF_REPORTED` with the caller as reporter, and returns the
+    /// rating in bps.
+    pub fn record_deception_rating(
+        &mut self,
+        bot_id: B256,
+        times_fooled_human: U256,
+        total_interactions: U256,
+    ) -> U256 {
+        let rating_bps =
+            detective_scoring::deception::rating_bps(times_fooled_human, total_interactions);
+        evm::log(DeceptionRated {
+            botId: bot_id,
+            method: registry::SELF_REPORTED,
+            reporter: msg::sender(),
+            fooled: times_fooled_human,
+            total: total_interactions,
+            ratingBps: rating_bps,
+        });
+        rating_bps
+    }
+
+    /// `verify_humanity_score` over a whole cycle: bit `i` of the result is
+    /// set when player `i` passes. At most `MAX_BATCH_SIZE` players.
+    pub fn verify_humanity_batch(
+        &self,
+        correct_guesses: Vec<U256>,
+        total_matches: Vec<U256>,
+        avg_response_times_ms: Vec<U256>,
+    ) -> Result<U256, VerifierError> {
+        let count = correct_guesses.len();
+        if total_matches.len() != count || avg_response_times_ms.len() != count {
+            return Err(VerifierError::ArrayLengthMismatch(ArrayLengthMismatch {}));
+        }
+        Self::check_batch_size(count)?;
+
+        let rule = self.policy.rule();
+        let mut passes = U256::ZERO;
+        for (i, ((&correct, &total), &latency)) in correct_guesses
+            .iter()
+            .zip(&total_matches)
+            .zip(&avg_response_times_ms)
+            .enumerate()
+        {
+            if rule.verdict(correct, total, latency).passed() {
+                passes.set_bit(i, true);
+            }
+        }
+        Ok(passes)
+    }
+
+    /// Batch form of `record_deception_rating` without the logging: ratings
+    /// in bps (not the whole percent of `calculate_deception_rating`) for
+    /// many bots at once. At most `MAX_BATCH_SIZE` bots.
+    pub fn deception_ratings_bps_batch(
+        &self,
+        times_fooled_human: Vec<U256>,
+        total_interactions: Vec<U256>,
+    ) -> Result<Vec<U256>, VerifierError> {
+        if times_fooled_human.len() != total_interactions.len() {
+            return Err(VerifierError::ArrayLengthMismatch(ArrayLengthMismatch {}));
+        }
+        Self::check_batch_size(times_fooled_human.len())?;
+        Ok(times_fooled_human
+            .iter()
+            .zip(&total_interactions)
+            .map(|(&fooled, &total)| detective_scoring::deception::rating_bps(fooled, total))
+            .collect())
+    }
+
+    /// Returns `(alphaWad, betaWad, zWad)`.
+    pub fn deception_prior(&self) -> (U256, U256, U256) {
+        self.deception_prior.get()
+    }
+
+    /// Sample-size-aware alternative to `calculate_deception_rating`.
+    /// Returns `(posteriorMeanBps, credibleLowerBoundBps)`; rank agents by
+    /// the lower bound so a bot with few encounters cannot top the board.
+    pub fn bayesian_deception_rating(
+        &self,
+        times_fooled_human: U256,
+        total_interactions: U256,
+    ) -> (U256, U256) {
+        self.deception_prior
+            .rate(times_fooled_human, total_interactions)
+    }
+
+    pub fn elo_k_factor(&self) -> U256 {
+        self.elo.k_factor()
+    }
+
+    /// Records one human-vs-bot encounter and updates both Elo ratings.
+    /// `guessed_correctly` means the human identified the bot.
+    pub fn record_encounter(
+        &mut self,
+        human: Address,
+        bot_id: B256,
+        guessed_correctly: bool,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        let (human_rating, bot_rating) = self
+            .elo
+            .record(human, bot_id, guessed_correctly)
+            .ok_or(VerifierError::RatingOverflow(RatingOverflow {}))?;
+
+        evm::log(EloUpdated {
+            human,
+            botId: bot_id,
+            guessedCorrectly: guessed_correctly,
+            humanRatingWad: human_rating,
+            botRatingWad: bot_rating,
+        });
+        Ok(())
+    }
+
+    /// Returns a human's `(ratingWad, matches)`; unrated players read 1500.
+    pub fn human_elo(&self, human: Address) -> (U256, U256) {
+        self.elo.human(human)
+    }
+
+    /// Returns a bot's `(ratingWad, matches)`; unrated bots read 1500.
+    pub fn bot_elo(&self, bot_id: B256) -> (U256, U256) {
+        self.elo.bot(bot_id)
+    }
+
+    /// Rates a whole game cycle as one Glicko-2 period. Entry `i` says
+    /// whether `humans[i]` identified `bot_ids[i]`. Periods must be rated in
+    /// increasing order, and each only once. Shorthand for
+    /// `add_glicko_encounters` followed by `rate_glicko_period`, for cycles
+    /// that fit in one call.
+    pub fn record_glicko_period(
+        &mut self,
+        cycle_id: U256,
+        humans: Vec<Address>,
+        bot_ids: Vec<B256>,
+        guessed_correctly: Vec<bool>,
+    ) -> Result<(), VerifierError> {
+        self.add_glicko_encounters(cycle_id, humans, bot_ids, guessed_correctly)?;
+        self.rate_glicko_period(cycle_id)
+    }
+
+    /// Adds encounters to Glicko-2 period `cycle_id` without rating it yet,
+    /// so cycles larger than `glicko::MAX_PERIOD_ENCOUNTERS` can be sent
+    /// over several calls. Fails while a different period is pending.
+    pub fn add_glicko_encounters(
+        &mut self,
+        cycle_id: U256,
+        humans: Vec<Address>,
+        bot_ids: Vec<B256>,
+        guessed_correctly: Vec<bool>,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        let pending =
+            self.glicko
+                .add_encounters(cycle_id, &humans, &bot_ids, &guessed_correctly)?;
+        evm::log(GlickoEncountersAdded {
+            period: cycle_id,
+            added: U256::from(humans.len()),
+            pending: U256::from(pending),
+        });
+        Ok(())
+    }
+
+    /// Rates pending period `cycle_id` from every encounter added to it.
+    pub fn rate_glicko_period(&mut self, cycle_id: U256) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        let encounters = self.glicko.rate_pending(cycle_id)?;
+        evm::log(GlickoPeriodRated {
+            period: cycle_id,
+            encounters: U256::from(encounters),
+        });
+        Ok(())
+    }
+
+    /// Returns `(rated, period)`: the latest game cycle rated with
+    /// Glicko-2, and whether any has been rated at all.
+    pub fn glicko_period(&self) -> (bool, U256) {
+        (self.glicko.rated_any(), self.glicko.current_period())
+    }
+
+    /// Returns `(open, period, encounters)` for the Glicko-2 period being
+    /// accumulated.
+    pub fn pending_glicko_period(&self) -> (bool, U256, U256) {
+        self.glicko.pending()
+    }
+
+    /// Returns a human's `(ratingWad, rdWad, volatilityWad, conservativeWad)`,
+    /// where `conservative = rating − 2·RD` is the value to rank by.
+    pub fn human_glicko(&self, human: Address) -> Result<(I256, U256, U256, I256), VerifierError> {
+        self.glicko
+            .human(human)
+            .and_then(|r| r.to_public())
+            .ok_or(VerifierError::RatingOverflow(RatingOverflow {}))
+    }
+
+    /// Returns a bot's `(ratingWad, rdWad, volatilityWad, conservativeWad)`.
+    pub fn bot_glicko(&self, bot_id: B256) -> Result<(I256, U256, U256, I256), VerifierError> {
+        self.glicko
+            .bot(bot_id)
+            .and_then(|r| r.to_public())
+            .ok_or(VerifierError::RatingOverflow(RatingOverflow {}))
+    }
+
+    /// Adds an AI model to the public benchmark. `model_id` is the hash of
+    /// the model's id string; `metadata_hash` points at its display metadata.
+    pub fn register_model(
+        &mut self,
+        model_id: B256,
+        operator: Address,
+        metadata_hash: B256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+        if operator == Address::ZERO {
+            return Err(VerifierError::InvalidAddress(InvalidAddress {}));
+        }
+        if self.models.exists(model_id) {
+            return Err(VerifierError::ModelAlreadyRegistered(
+                ModelAlreadyRegistered { modelId: model_id },
+            ));
+        }
+        self.models.register(
+            model_id,
+            operator,
+            metadata_hash,
+            U256::from(block::timestamp()),
+        );
+        evm::log(ModelRegistered {
+            modelId: model_id,
+            operator,
+            metadataHash: metadata_hash,
+        });
+        Ok(())
+    }
+
+    /// Lets a model's operator point at new display metadata.
+    pub fn set_model_metadata(
+        &mut self,
+        model_id: B256,
+        metadata_hash: B256,
+    ) -> Result<(), VerifierError> {
+        self.only_model_operator(model_id)?;
+        self.models.set_metadata(model_id, metadata_hash);
+        evm::log(ModelMetadataUpdated {
+            modelId: model_id,
+            metadataHash: metadata_hash,
+        });
+        Ok(())
+    }
+
+    /// Hands a model over to a new operator.
+    pub fn set_model_operator(
+        &mut self,
+        model_id: B256,
+        operator: Address,
+    ) -> Result<(), VerifierError> {
+        self.only_model_operator(model_id)?;
+        if operator == Address::ZERO {
+            return Err(VerifierError::InvalidAddress(InvalidAddress {}));
+        }
+        self.models.set_operator(model_id, operator);
+        evm::log(ModelOperatorUpdated {
+            modelId: model_id,
+            operator,
+        });
+        Ok(())
+    }
+
+    /// Adds a batch of encounter outcomes for a model: `encounters` humans
+    /// faced it and `fooled` of them voted "human".
+    pub fn record_model_outcomes(
+        &mut self,
+        model_id: B256,
+        encounters: U256,
+        fooled: U256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        if !self.models.exists(model_id) {
+            return Err(VerifierError::UnknownModel(UnknownModel {
+                modelId: model_id,
+            }));
+        }
+        if fooled > encounters {
+            return Err(VerifierError::InvalidOutcome(InvalidOutcome {}));
+        }
+        self.models
+            .record(model_id, encounters, fooled)
+            .ok_or(VerifierError::InvalidOutcome(InvalidOutcome {}))?;
+        evm::log(ModelOutcomesRecorded {
+            modelId: model_id,
+            encounters,
+            fooled,
+        });
+        Ok(())
+    }
+
+    /// Returns `(operator, metadataHash, registeredAt)`.
+    pub fn model_info(&self, model_id: B256) -> (Address, B256, U256) {
+        self.models.info(model_id)
+    }
+
+    /// Returns `(encounters, fooled, dsrBps, daBps)`: Deception Success Rate
+    /// and its inverse, Detection Accuracy, in basis points.
+    pub fn model_stats(&self, model_id: B256) -> (U256, U256, U256, U256) {
+        self.models.stats(model_id)
+    }
+
+    pub fn model_count(&self) -> U256 {
+        U256::from(self.models.len())
+    }
+
+    /// Model id by registration index, for enumerating the benchmark.
+    pub fn model_at(&self, index: U256) -> Result<B256, VerifierError> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.models.id_at(i))
+            .ok_or(VerifierError::UnknownModel(UnknownModel {
+                modelId: B256::ZERO,
+            }))
+    }
+
+    /// Adds a batch of impersonation outcomes for persona `fid`: bots posed
+    /// as it `attempts` times and fooled the human `successes` times.
+    pub fn record_persona_outcomes(
+        &mut self,
+        fid: U256,
+        attempts: U256,
+        successes: U256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        if successes > attempts {
+            return Err(VerifierError::InvalidOutcome(InvalidOutcome {}));
+        }
+        self.personas
+            .record(fid, attempts, successes)
+            .ok_or(VerifierError::InvalidOutcome(InvalidOutcome {}))?;
+        evm::log(PersonaOutcomesRecorded {
+            fid,
+            attempts,
+            successes,
+        });
+        Ok(())
+    }
+
+    /// Returns `(attempts, successes, difficultyBps)` for a persona, where
+    /// difficulty is the complement of its smoothed deception rate.
+    pub fn persona_difficulty(&self, fid: U256) -> (U256, U256, U256) {
+        self.personas.get(fid, &self.deception_prior)
+    }
+
+    /// Number of personas with at least one recorded attempt.
+    pub fn persona_count(&self) -> U256 {
+        U256::from(self.personas.len())
+    }
+
+    /// FIDs hardest to impersonate, among personas with at least
+    /// `min_attempts` attempts. Only the `limit` personas from `offset` (in
+    /// first-seen order, at most 200 per call) are considered; page through
+    /// `persona_count` to rank them all. At most 50 are returned.
+    pub fn hardest_personas(
+        &self,
+        min_attempts: U256,
+        offset: U256,
+        limit: U256,
+        count: U256,
+    ) -> Vec<U256> {
+        self.personas.ranked(
+            &self.deception_prior,
+            min_attempts,
+            offset.saturating_to(),
+            limit.saturating_to(),
+            count.saturating_to(),
+            true,
+        )
+    }
+
+    /// FIDs easiest to impersonate, among personas with at least
+    /// `min_attempts` attempts. Only the `limit` personas from `offset` (in
+    /// first-seen order, at most 200 per call) are considered; page through
+    /// `persona_count` to rank them all. At most 50 are returned.
+    pub fn easiest_personas(
+        &self,
+        min_attempts: U256,
+        offset: U256,
+        limit: U256,
+        count: U256,
+    ) -> Vec<U256> {
+        self.personas.ranked(
+            &self.deception_prior,
+            min_attempts,
+            offset.saturating_to(),
+            limit.saturating_to(),
+            count.saturating_to(),
+            false,
+        )
+    }
+
+    /// Opens a match for commit–reveal voting, sealing its answer as
+    /// `truth_commitment` (see `truth_commitment`). Commits are accepted
+    /// until `commit_deadline`, reveals after it until `reveal_deadline`.
+    pub fn open_match(
+        &mut self,
+        match_id: B256,
+        truth_commitment: B256,
+        commit_deadline: U256,
+        reveal_deadline: U256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        self.voting.open_match(
+            match_id,
+            truth_commitment,
+            commit_deadline,
+            reveal_deadline,
+            U256::from(block::timestamp()),
+        )
+    }
+
+    /// Commits to a hidden vote; see `vote_commitment` for the encoding.
+    /// Registered players only, and not while paused (V4 `submitVote`).
+    pub fn commit_vote(&mut self, match_id: B256, commitment: B256) -> Result<(), VerifierError> {
+        self.entry.when_not_paused()?;
+        self.entry.only_registered(msg::sender())?;
+        self.voting.commit(
+            match_id,
+            msg::sender(),
+            commitment,
+            U256::from(block::timestamp()),
+        )
+    }
+
+    /// Opens a previously committed vote once the commit window has closed.
+    pub fn reveal_vote(
+        &mut self,
+        match_id: B256,
+        is_bot: bool,
+        salt: B256,
+    ) -> Result<(), VerifierError> {
+        self.voting.reveal(
+            match_id,
+            msg::sender(),
+            is_bot,
+            salt,
+            U256::from(block::timestamp()),
+        )
+    }
+
+    /// Proposes a match's result by revealing its sealed answer after vote
+    /// reveals close. Only answers matching the commitment made at opening
+    /// are accepted; the result is final once its challenge window passes.
+    pub fn reveal_ground_truth(
+        &mut self,
+        match_id: B256,
+        opponent_is_bot: bool,
+        salt: B256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(RESULT_ORACLE_ROLE)?;
+        let now = U256::from(block::timestamp());
+        self.voting
+            .reveal_truth(match_id, opponent_is_bot, salt, now)?;
+        self.results
+            .propose(match_id, msg::sender(), opponent_is_bot, now)
+    }
+
+    /// Disputes a proposed result within its challenge window. The attached
+    /// value is the bond and must be at least the dispute bond.
+    #[payable]
+    pub fn dispute_result(&mut self, match_id: B256) -> Result<(), VerifierError> {
+        self.entry.only_registered(msg::sender())?;
+        self.results.dispute(
+            match_id,
+            msg::sender(),
+            msg::value(),
+            U256::from(block::timestamp()),
+        )?;
+        self.ledger.hold(NATIVE, msg::value());
+        Ok(())
+    }
+
+    /// Rules on a disputed result, making it final. The ruling is not
+    /// checked against the sealed answer, so the arbiter can override it.
+    /// The bond is credited to the challenger if the proposal is overturned,
+    /// else to the proposer.
+    pub fn resolve_dispute(
+        &mut self,
+        match_id: B256,
+        opponent_is_bot: bool,
+    ) -> Result<(), VerifierError> {
+        self.only_role(ARBITER_ROLE)?;
+        let (winner, bond) = self.results.resolve(match_id, opponent_is_bot)?;
+        self.ledger.release(NATIVE, bond);
+        self.ledger.credit(winner, NATIVE, bond);
+        Ok(())
+    }
+
+    /// Voids a match whose result is still not final well after its reveal
+    /// and challenge windows closed (see `disputes::RESULT_TIMEOUT`).
+    /// Callable by anyone: every stake and any pending dispute bond is
+    /// refunded to the pull-payment ledger, and its votes are dropped
+    /// ungraded.
+    pub fn void_match(&mut self, match_id: B256) -> Result<(), VerifierError> {
+        let (opened_at, _, _, reveal_deadline, _) = self.voting.match_of(match_id);
+        if opened_at == U256::ZERO {
+            return Err(VerifierError::UnknownMatch(UnknownMatch {
+                matchId: match_id,
+            }));
+        }
+        let now = U256::from(block::timestamp());
+        if let Some((challenger, bond)) = self.results.void(match_id, reveal_deadline, now)? {
+            self.ledger.release(NATIVE, bond);
+            self.ledger.credit(challenger, NATIVE, bond);
+        }
+        self.stakes.refund(match_id, &mut self.ledger)
+    }
+
+    pub fn is_match_voided(&self, match_id: B256) -> bool {
+        self.results.is_voided(match_id)
+    }
+
+    /// Returns `(proposedAt, proposer, challenger, bond, resolved)`.
+    pub fn result_status(&self, match_id: B256) -> (U256, Address, Address, U256, bool) {
+        self.results.result_of(match_id)
+    }
+
+    /// Returns `(challengePeriod, disputeBond)`.
+    pub fn dispute_params(&self) -> (U256, U256) {
+        self.results.params()
+    }
+
+    /// Grades `wallet`'s vote in a match with a final result into its
+    /// on-chain record. Unrevealed commitments are graded as forfeits. Votes
+    /// in a voided match are dropped without being graded.
+    pub fn tally_vote(&mut self, match_id: B256, wallet: Address) -> Result<(), VerifierError> {
+        if self.results.is_voided(match_id) {
+            return self.voting.discard(match_id, wallet);
+        }
+        let answer = self
+            .results
+            .final_outcome(match_id, U256::from(block::timestamp()))?;
+        self.voting.tally(match_id, wallet, answer)
+    }
+
+    /// Runs the humanity rule on `wallet`'s graded on-chain votes rather than
+    /// caller-supplied numbers, registering a pass. Reverts while any of the
+    /// wallet's commitments is untallied, so a player cannot leave wrong
+    /// answers or forfeits out of the record by tallying selectively.
+    pub fn verify_vote_record(&mut self, wallet: Address) -> Result<bool, VerifierError> {
+        let pending = self.voting.untallied_of(wallet);
+        if pending != U256::ZERO {
+            return Err(VerifierError::UntalliedVotes(UntalliedVotes {
+                wallet,
+                pending,
+            }));
+        }
+        let (correct, total, _, avg_latency_ms) = self.voting.record_of(wallet);
+        let passed = self.check_humanity(
+            wallet,
+            VerificationMethod::CommitReveal as u8,
+            correct,
+            total,
+            avg_latency_ms,
+        );
+        if passed {
+            self.registry.record(
+                wallet,
+                VerificationMethod::CommitReveal,
+                U256::from(block::timestamp()),
+            );
+        }
+        Ok(passed)
+    }
+
+    /// Commitments by `wallet` still waiting to be tallied.
+    pub fn untallied_votes(&self, wallet: Address) -> U256 {
+        self.voting.untallied_of(wallet)
+    }
+
+    /// Returns `(correct, total, forfeits, avgLatencyMs)` from graded votes.
+    pub fn vote_record(&self, wallet: Address) -> (U256, U256, U256, U256) {
+        self.voting.record_of(wallet)
+    }
+
+    /// Returns `(commitment, revealed, isBot)`.
+    pub fn vote_of(&self, match_id: B256, wallet: Address) -> (B256, bool, bool) {
+        self.voting.vote_of(match_id, wallet)
+    }
+
+    /// Returns `(openedAt, truthCommitment, commitDeadline, revealDeadline,
+    /// settled, opponentIsBot)`; `settled` means the result is final.
+    pub fn match_info(&self, match_id: B256) -> (U256, B256, U256, U256, bool, bool) {
+        let (opened_at, truth_commitment, commit_deadline, reveal_deadline, _) =
+            self.voting.match_of(match_id);
+        let outcome = self
+            .results
+            .outcome(match_id, U256::from(block::timestamp()));
+        (
+            opened_at,
+            truth_commitment,
+            commit_deadline,
+            reveal_deadline,
+            outcome.is_some(),
+            outcome.unwrap_or(false),
+        )
+    }
+
+    /// Helper for frontends: the commitment to submit for a vote.
+    pub fn vote_commitment(
+        &self,
+        match_id: B256,
+        is_bot: bool,
+        salt: B256,
+        wallet: Address,
+    ) -> B256 {
+        voting::vote_commitment(match_id, is_bot, salt, wallet)
+    }
+
+    /// Helper for the game server: the seal to open a match with.
+    pub fn truth_commitment(&self, match_id: B256, opponent_is_bot: bool, salt: B256) -> B256 {
+        voting::truth_commitment(match_id, opponent_is_bot, salt)
+    }
+
+    /// Registers the caller for the game. The attached fee must be at least
+    /// `min_entry_fee` and is kept by the contract.
+    #[payable]
+    pub fn register_for_game(&mut self) -> Result<(), VerifierError> {
+        self.entry.register(msg::sender(), msg::value())
+    }
+
+    pub fn is_wallet_registered(&self, wallet: Address) -> bool {
+        self.entry.is_registered(wallet)
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> Result<(), VerifierError> {
+        self.only_role(PAUSER_ROLE)?;
+        self.entry.set_paused(paused);
+        Ok(())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.entry.is_paused()
+    }
+
+    pub fn min_entry_fee(&self) -> U256 {
+        self.entry.min_entry_fee()
+    }
+
+    /// Stakes the attached native value on whether `match_id`'s opponent is
+    /// a bot. Stakes close with the match's commit window.
+    #[payable]
+    pub fn stake_on_match(
+        &mut self,
+        match_id: B256,
+        is_bot: bool,
+        deadline: U256,
+    ) -> Result<(), VerifierError> {
+        let now = self.stake_preconditions(match_id)?;
+        self.stakes.place(
+            match_id,
+            msg::sender(),
+            NATIVE,
+            is_bot,
+            msg::value(),
+            deadline,
+            now,
+            &mut self.ledger,
+        )
+    }
+
+    /// Like `stake_on_match`, pulling `amount` USDC from the caller. Needs a
+    /// prior `approve` of this contract.
+    pub fn stake_on_match_usdc(
+        &mut self,
+        match_id: B256,
+        is_bot: bool,
+        amount: U256,
+        deadline: U256,
+    ) -> Result<(), VerifierError> {
+        let now = self.stake_preconditions(match_id)?;
+        let sender = msg::sender();
+        self.stakes.place(
+            match_id,
+            sender,
+            USDC,
+            is_bot,
+            amount,
+            deadline,
+            now,
+            &mut self.ledger,
+        )?;
+
+        let usdc = IERC20::new(USDC);
+        match usdc.transfer_from(Call::new_in(self), sender, contract::address(), amount) {
+            Ok(true) => Ok(()),
+            _ => Err(VerifierError::USDCTransferFailed(USDCTransferFailed {})),
+        }
+    }
+
+    /// Returns `(amount, token, isBot)`; `token` is zero for native stakes.
+    pub fn stake_of(&self, match_id: B256, wallet: Address) -> (U256, Address, bool) {
+        self.stakes.stake_of(match_id, wallet)
+    }
+
+    /// Returns `(botPool, humanPool)` staked in `token` on a match.
+    pub fn stake_pools(&self, match_id: B256, token: Address) -> (U256, U256) {
+        self.stakes.pools_of(match_id, token)
+    }
+
+    /// Pays out a match's stakes from its final result into the pull-payment
+    /// ledger. Callable by anyone once the result is final.
+    pub fn settle_stakes(&mut self, match_id: B256) -> Result<(), VerifierError> {
+        let opponent_is_bot = self
+            .results
+            .final_outcome(match_id, U256::from(block::timestamp()))?;
+        self.stakes
+            .settle(match_id, opponent_is_bot, &mut self.ledger)
+    }
+
+    pub fn stakes_settled(&self, match_id: B256) -> bool {
+        self.stakes.is_settled(match_id)
+    }
+
+    pub fn house_fee_bps(&self) -> U256 {
+        self.stakes.house_fee_bps()
+    }
+
+    /// Amount of `token` (zero address for native) `user` can withdraw.
+    pub fn get_pending_reward(&self, user: Address, token: Address) -> U256 {
+        self.ledger.pending_of(user, token)
+    }
+
+    /// Withdraws the caller's whole pending balance in `token` (zero address
+    /// for native). The balance is cleared before the transfer.
+    pub fn claim_rewards(&mut self, token: Address) -> Result<(), VerifierError> {
+        self.ledger.lock()?;
+        let wallet = msg::sender();
+        let amount = self.ledger.take(wallet, token)?;
+        self.pay(token, wallet, amount)?;
+        evm::log(Withdrawal {
+            wallet,
+            token,
+            amount,
+        });
+        self.ledger.unlock();
+        Ok(())
+    }
+
+    /// Sends `amount` of house funds to the house wallet. Funds backing
+    /// pending claims or held in escrow cannot be withdrawn.
+    pub fn withdraw_house_funds(
+        &mut self,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), VerifierError> {
+        self.only_role(TREASURER_ROLE)?;
+        self.ledger.lock()?;
+        let available = self.house_available(token)?;
+        if amount == U256::ZERO || amount > available {
+            return Err(VerifierError::InsufficientHouseFunds(
+                InsufficientHouseFunds { available },
+            ));
+        }
+        let house = self.ledger.house_wallet();
+        self.pay(token, house, amount)?;
+        evm::log(HouseFundsWithdrawn { token, amount });
+        self.ledger.unlock();
+        Ok(())
+    }
+
+    /// Balance of `token` held by the contract minus what players are owed
+    /// and what is escrowed for unsettled matches and disputes.
+    pub fn house_available(&self, token: Address) -> Result<U256, VerifierError> {
+        let balance = if token == NATIVE {
+            contract::balance()
+        } else {
+            IERC20::new(token)
+                .balance_of(Call::new(), contract::address())
+                .map_err(|_| VerifierError::USDCTransferFailed(USDCTransferFailed {}))?
+        };
+        Ok(self.ledger.house_available(token, balance))
+    }
+
+    /// Sum of every wallet's pending balance in `token`.
+    pub fn total_pending(&self, token: Address) -> U256 {
+        self.ledger.total_pending(token)
+    }
+
+    /// Stakes and dispute bonds in `token` awaiting a match result.
+    pub fn total_escrowed(&self, token: Address) -> U256 {
+        self.ledger.total_escrowed(token)
+    }
+
+    pub fn house_wallet(&self) -> Address {
+        self.ledger.house_wallet()
+    }
+}
+
+impl DetectiveStylusVerifier {
+    fn compute_latency_stats(latencies_ms: &[U256]) -> Result<LatencyStats, VerifierError> {
+        if latencies_ms.len() > stats::MAX_LATENCY_SAMPLES {
+            return Err(VerifierError::TooManySamples(TooManySamples {
+                count: U256::from(latencies_ms.len()),
+                max: U256::from(stats::MAX_LATENCY_SAMPLES),
+            }));
+        }
+        if latencies_ms.is_empty() {
+            return Err(VerifierError::NoSamples(NoSamples {}));
+        }
+        stats::latency_stats(latencies_ms).ok_or(VerifierError::StatsOverflow(StatsOverflow {}))
+    }
+
+    fn apply_change(&mut self, change: ChangeKind, params: &[u8]) -> Result<(), VerifierError> {
+        use timelock::decode;
+        match change {
+            ChangeKind::HumanityThresholds => {
+                let (min_accuracy_pct, min_latency_ms, max_latency_ms) = decode(params)?;
+                self.policy
+                    .update(min_accuracy_pct, min_latency_ms, max_latency_ms)
+            }
+            ChangeKind::DistributionThresholds => {
+                let (min_latency_cv_bps, min_single_latency_ms) = decode(params)?;
+                self.policy
+                    .update_distribution(min_latency_cv_bps, min_single_latency_ms)
+            }
+            ChangeKind::ConfidenceThresholds => {
+                let (wilson_z_wad, min_matches, min_accuracy_lower_bound_bps) = decode(params)?;
+                self.policy.update_confidence(
+                    wilson_z_wad,
+                    min_matches,
+                    min_accuracy_lower_bound_bps,
+                )
+            }
+            ChangeKind::VerificationPeriod => {
+                let seconds: U256 = decode(params)?;
+                if seconds == U256::ZERO {
+                    return Err(VerifierError::InvalidPeriod(InvalidPeriod {}));
+                }
+                self.registry.set_period(seconds);
+                Ok(())
+            }
+            ChangeKind::DeceptionPrior => {
+                let (alpha_wad, beta_wad, z_wad) = decode(params)?;
+                self.deception_prior.update(alpha_wad, beta_wad, z_wad)
+            }
+            ChangeKind::EloKFactor => {
+                let k_factor: U256 = decode(params)?;
+                if k_factor == U256::ZERO || k_factor > U256::from(elo::MAX_K_FACTOR) {
+                    return Err(VerifierError::InvalidKFactor(InvalidKFactor {}));
+                }
+                self.elo.set_k_factor(k_factor);
+                evm::log(KFactorUpdated { kFactor: k_factor });
+                Ok(())
+            }
+            ChangeKind::DisputeParams => {
+                let (challenge_period, dispute_bond) = decode(params)?;
+                self.results.update(challenge_period, dispute_bond)
+            }
+            ChangeKind::MinEntryFee => {
+                self.entry.set_min_entry_fee(decode(params)?);
+                Ok(())
+            }
+            ChangeKind::HouseFee => self.stakes.set_house_fee_bps(decode(params)?),
+            ChangeKind::HouseWallet => self.ledger.set_house_wallet(decode(params)?),
+            ChangeKind::GameServerSigner => {
+                let signer: Address = decode(params)?;
+                if signer == Address::ZERO {
+                    return Err(VerifierError::InvalidAddress(InvalidAddress {}));
+                }
+                self.game_server_signer.set(signer);
+                Ok(())
+            }
+            ChangeKind::TimelockDelay => self.timelock.set_delay(decode(params)?),
+        }
+    }
+
+    fn check_batch_size(count: usize) -> Result<(), VerifierError> {
+        if count > MAX_BATCH_SIZE {
+            return Err(VerifierError::BatchTooLarge(BatchTooLarge {
+                count: U256::from(count),
+                max: U256::from(MAX_BATCH_SIZE),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Runs the humanity rule and logs `HumanityChecked`; `method` says what
+    /// backs the numbers (`SELF_REPORTED` when nothing does).
+    fn check_humanity(
+        &mut self,
+        wallet: Address,
+        method: u8,
+        correct_guesses: U256,
+        total_matches: U256,
+        avg_response_time_ms: U256,
+    ) -> bool {
+        let passed =
+            self.verify_humanity_score(correct_guesses, total_matches, avg_response_time_ms);
+        evm::log(HumanityChecked {
+            wallet,
+            method,
+            reporter: msg::sender(),
+            accuracyBps: confidence::ratio_bps(correct_guesses, total_matches),
+            avgLatency: avg_response_time_ms,
+            passed,
+            policyVersion: self.policy.version(),
+        });
+        passed
+    }
+
+    fn only_role(&self, role: B256) -> Result<(), VerifierError> {
+        self.access.check_role(role, msg::sender())
+    }
+
+    fn only_model_operator(&self, model_id: B256) -> Result<(), VerifierError> {
+        if !self.models.exists(model_id) {
+            return Err(VerifierError::UnknownModel(UnknownModel {
+                modelId: model_id,
+            }));
+        }
+        if msg::sender() != self.models.operator(model_id) {
+            return Err(VerifierError::NotModelOperator(NotModelOperator {
+                modelId: model_id,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Shared checks for both staking entrypoints; returns the current time.
+    fn stake_preconditions(&self, match_id: B256) -> Result<U256, VerifierError> {
+        self.entry.when_not_paused()?;
+        self.entry.only_registered(msg::sender())?;
+        let now = U256::from(block::timestamp());
+        self.voting.ensure_commit_open(match_id, now)?;
+        Ok(now)
+    }
+
+    /// Sends `amount` of `token` (zero address for native) to `to`.
+    fn pay(&mut self, token: Address, to: Address, amount: U256) -> Result<(), VerifierError> {
+        if token == NATIVE {
+            return call::transfer_eth(to, amount)
+                .map_err(|_| VerifierError::TransferFailed(TransferFailed {}));
+        }
+        match IERC20::new(token).transfer(Call::new_in(self), to, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(VerifierError::USDCTransferFailed(USDCTransferFailed {})),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::SolValue;
+    use stylus_sdk::alloy_primitives::{address, Address};
+
+    use super::*;
+
+    /// `msg::sender()` in every motsu test.
+    const SENDER: Address = address!("DeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF");
+    const ADMIN: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+
+    fn is_unauthorized(result: Result<(), VerifierError>) -> bool {
+        matches!(
+            result,
+            Err(VerifierError::AccessControlUnauthorizedAccount(_))
+        )
+    }
+
+    #[motsu::test]
+    fn role_changes_need_the_role_admin(contract: DetectiveStylusVerifier) {
+        assert!(is_unauthorized(contract.grant_role(PAUSER_ROLE, BOB)));
+        assert!(is_unauthorized(contract.revoke_role(PAUSER_ROLE, BOB)));
+        assert!(is_unauthorized(
+            contract.set_role_admin(PAUSER_ROLE, ARBITER_ROLE)
+        ));
+
+        contract.access.grant(DEFAULT_ADMIN_ROLE, SENDER, ADMIN);
+        assert!(contract.grant_role(PAUSER_ROLE, BOB).is_ok());
+        assert!(contract.has_role(PAUSER_ROLE, BOB));
+        assert!(contract.revoke_role(PAUSER_ROLE, BOB).is_ok());
+        assert!(!contract.has_role(PAUSER_ROLE, BOB));
+    }
+
+    #[motsu::test]
+    fn role_admin_can_be_delegated(contract: DetectiveStylusVerifier) {
+        contract.access.grant(DEFAULT_ADMIN_ROLE, SENDER, ADMIN);
+        assert!(contract.set_role_admin(ARBITER_ROLE, PAUSER_ROLE).is_ok());
+        assert_eq!(contract.get_role_admin(ARBITER_ROLE), PAUSER_ROLE);
+        // Holding DEFAULT_ADMIN_ROLE is no longer enough.
+        assert!(is_unauthorized(contract.grant_role(ARBITER_ROLE, BOB)));
+
+        contract.access.grant(PAUSER_ROLE, SENDER, ADMIN);
+        assert!(contract.grant_role(ARBITER_ROLE, BOB).is_ok());
+    }
+
+    #[motsu::test]
+    fn renounce_needs_the_callers_confirmation(contract: DetectiveStylusVerifier) {
+        contract.access.grant(PAUSER_ROLE, SENDER, ADMIN);
+        assert!(matches!(
+            contract.renounce_role(PAUSER_ROLE, BOB),
+            Err(VerifierError::AccessControlBadConfirmation(_))
+        ));
+        assert!(contract.has_role(PAUSER_ROLE, SENDER));
+        assert!(contract.renounce_role(PAUSER_ROLE, SENDER).is_ok());
+        assert!(!contract.has_role(PAUSER_ROLE, SENDER));
+    }
+
+    #[motsu::test]
+    fn default_admin_role_cannot_move_through_role_calls(contract: DetectiveStylusVerifier) {
+        contract.access.grant(DEFAULT_ADMIN_ROLE, SENDER, ADMIN);
+        let enforced = |result: Result<(), VerifierError>| {
+            matches!(
+                result,
+                Err(VerifierError::AccessControlEnforcedDefaultAdminRules(_))
+            )
+        };
+        assert!(enforced(contract.grant_role(DEFAULT_ADMIN_ROLE, BOB)));
+        assert!(enforced(contract.revoke_role(DEFAULT_ADMIN_ROLE, SENDER)));
+        assert!(enforced(contract.renounce_role(DEFAULT_ADMIN_ROLE, SENDER)));
+        assert!(enforced(
+            contract.set_role_admin(DEFAULT_ADMIN_ROLE, PAUSER_ROLE)
+        ));
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, SENDER));
+        assert!(!contract.has_role(DEFAULT_ADMIN_ROLE, BOB));
+    }
+    /// Makes `admin` the admin with every admin role, as `initialize` does.
+    fn set_admin(contract: &mut DetectiveStylusVerifier, admin: Address) {
+        contract.admin.set(admin);
+        for role in [DEFAULT_ADMIN_ROLE].into_iter().chain(MANAGEMENT_ROLES) {
+            contract.access.grant(role, admin, admin);
+        }
+    }
+
+    #[motsu::test]
+    fn accepting_the_handover_moves_every_admin_role(contract: DetectiveStylusVerifier) {
+        set_admin(contract, ADMIN);
+        // As if `ADMIN` had called `propose_admin(SENDER)`.
+        contract.pending_admin.set(SENDER);
+
+        assert!(contract.accept_admin().is_ok());
+        for role in [DEFAULT_ADMIN_ROLE].into_iter().chain(MANAGEMENT_ROLES) {
+            assert!(contract.has_role(role, SENDER));
+            assert!(!contract.has_role(role, ADMIN));
+        }
+        assert_eq!(contract.admin.get(), SENDER);
+        assert_eq!(contract.pending_admin(), Address::ZERO);
+    }
+
+    #[motsu::test]
+    fn only_the_nominee_can_accept(contract: DetectiveStylusVerifier) {
+        set_admin(contract, ADMIN);
+        assert!(matches!(
+            contract.accept_admin(),
+            Err(VerifierError::NotPendingAdmin(_))
+        ));
+        contract.pending_admin.set(BOB);
+        assert!(matches!(
+            contract.accept_admin(),
+            Err(VerifierError::NotPendingAdmin(_))
+        ));
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, ADMIN));
+        assert!(!contract.has_role(DEFAULT_ADMIN_ROLE, SENDER));
+    }
+
+    #[motsu::test]
+    fn only_the_admin_can_nominate(contract: DetectiveStylusVerifier) {
+        set_admin(contract, ADMIN);
+        assert!(is_unauthorized(contract.propose_admin(SENDER)));
+        assert_eq!(contract.pending_admin(), Address::ZERO);
+
+        set_admin(contract, SENDER);
+        assert!(contract.propose_admin(BOB).is_ok());
+        assert_eq!(contract.pending_admin(), BOB);
+        // Proposing zero cancels.
+        assert!(contract.propose_admin(Address::ZERO).is_ok());
+        assert_eq!(contract.pending_admin(), Address::ZERO);
+    }
+
+    #[motsu::test]
+    fn each_change_kind_needs_its_role(contract: DetectiveStylusVerifier) {
+        assert!(contract
+            .timelock
+            .set_delay(U256::from(timelock::DEFAULT_DELAY))
+            .is_ok());
+        let fee: Bytes = (U256::from(500),).abi_encode().into();
+        let house_fee = ChangeKind::HouseFee as u8;
+        let delay = ChangeKind::TimelockDelay as u8;
+
+        assert!(matches!(
+            contract.schedule_change(house_fee, fee.clone()),
+            Err(VerifierError::AccessControlUnauthorizedAccount(_))
+        ));
+        assert!(matches!(
+            contract.schedule_change(13, fee.clone()),
+            Err(VerifierError::UnknownChangeKind(_))
+        ));
+
+        contract.access.grant(TREASURER_ROLE, SENDER, ADMIN);
+        assert!(contract.schedule_change(house_fee, fee.clone()).is_ok());
+        assert!(matches!(
+            contract.schedule_change(delay, fee.clone()),
+            Err(VerifierError::AccessControlUnauthorizedAccount(_))
+        ));
+        assert_eq!(
+            contract.change_eta(house_fee, fee.clone()),
+            U256::from(block::timestamp() + timelock::DEFAULT_DELAY)
+        );
+        assert!(matches!(
+            contract.execute_change(house_fee, fee.clone()),
+            Err(VerifierError::ChangeNotReady(_))
+        ));
+
+        contract.access.revoke(TREASURER_ROLE, SENDER, ADMIN);
+        assert!(is_unauthorized(
+            contract.cancel_change(house_fee, fee.clone())
+        ));
+        assert!(is_unauthorized(contract.execute_change(house_fee, fee)));
+    }
+}
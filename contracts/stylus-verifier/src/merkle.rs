@@ -0,0 +1,108 @@
+//! Merkle inclusion proofs for per-cycle results.
+//!
+//! Hashing matches OpenZeppelin's `StandardMerkleTree` (double-hashed
+//! `abi.encode` leaves, sorted-pair internal nodes) so the backend can build
+//! trees with `@openzeppelin/merkle-tree` and players can reuse its proofs.
+
+use alloy_sol_types::SolValue;
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    crypto::keccak,
+};
+
+/// Leaf for `(wallet, correct, total, avgLatency)`:
+/// `keccak256(bytes.concat(keccak256(abi.encode(...))))`.
+pub fn player_leaf(
+    wallet: Address,
+    correct_guesses: U256,
+    total_matches: U256,
+    avg_response_time_ms: U256,
+) -> B256 {
+    let encoded = (wallet, correct_guesses, total_matches, avg_response_time_ms).abi_encode();
+    keccak(keccak(encoded))
+}
+
+/// Walks `proof` from `leaf` and checks the result against `root`.
+pub fn verify(proof: &[B256], root: B256, leaf: B256) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(node, *sibling));
+    computed == root
+}
+
+fn hash_pair(a: B256, b: B256) -> B256 {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(lo.as_slice());
+    buf[32..].copy_from_slice(hi.as_slice());
+    keccak(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+
+    fn leaf(wallet: Address, correct: u64) -> B256 {
+        player_leaf(
+            wallet,
+            U256::from(correct),
+            U256::from(10),
+            U256::from(2_500),
+        )
+    }
+
+    /// Sorted-pair node, built independently of `hash_pair`.
+    fn node(a: B256, b: B256) -> B256 {
+        let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+        keccak([lo.as_slice(), hi.as_slice()].concat())
+    }
+
+    #[test]
+    fn leaf_double_hashes_the_abi_encoding() {
+        let mut encoded = [0u8; 128];
+        encoded[12..32].copy_from_slice(ALICE.as_slice());
+        encoded[63] = 7;
+        encoded[95] = 10;
+        encoded[126..].copy_from_slice(&2_500u16.to_be_bytes());
+        assert_eq!(leaf(ALICE, 7), keccak(keccak(encoded)));
+    }
+
+    #[test]
+    fn single_leaf_tree_needs_no_proof() {
+        let alice = leaf(ALICE, 7);
+        assert!(verify(&[], alice, alice));
+        assert!(!verify(&[], alice, leaf(BOB, 7)));
+    }
+
+    #[test]
+    fn verifies_every_leaf_of_a_four_leaf_tree() {
+        let leaves = [leaf(ALICE, 7), leaf(BOB, 3), leaf(ALICE, 1), leaf(BOB, 9)];
+        let left = node(leaves[0], leaves[1]);
+        let right = node(leaves[2], leaves[3]);
+        let root = node(left, right);
+
+        assert!(verify(&[leaves[1], right], root, leaves[0]));
+        assert!(verify(&[leaves[0], right], root, leaves[1]));
+        assert!(verify(&[leaves[3], left], root, leaves[2]));
+        assert!(verify(&[leaves[2], left], root, leaves[3]));
+    }
+
+    #[test]
+    fn rejects_a_bad_leaf_or_proof() {
+        let (a, b, c) = (leaf(ALICE, 7), leaf(BOB, 3), leaf(ALICE, 1));
+        let root = node(node(a, b), c);
+
+        assert!(verify(&[b, c], root, a));
+        // A different score for the same wallet.
+        assert!(!verify(&[b, c], root, leaf(ALICE, 8)));
+        // Truncated, padded and reordered proofs.
+        assert!(!verify(&[b], root, a));
+        assert!(!verify(&[b, c, c], root, a));
+        assert!(!verify(&[c, b], root, a));
+    }
+}
@@ -0,0 +1,148 @@
+//! Public benchmark of AI models: which ones fool humans most.
+//!
+//! Each model is identified by a hash of its model id (e.g.
+//! `keccak256("anthropic/claude-sonnet-4")`) and carries an operator and a
+//! hash of its display metadata. The result oracle feeds encounter outcomes,
+//! so Deception Success Rate and Detection Accuracy (as defined in
+//! `docs/RESEARCH_API.md`) are stored on-chain rather than in Postgres.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    prelude::*,
+    storage::{StorageAddress, StorageB256, StorageMap, StorageU256, StorageVec},
+};
+
+use detective_scoring::deception;
+use detective_scoring::math::BPS;
+
+#[storage]
+pub struct ModelRecord {
+    /// Zero means the model is not registered.
+    operator: StorageAddress,
+    metadata_hash: StorageB256,
+    registered_at: StorageU256,
+    /// Humans who faced this model.
+    encounters: StorageU256,
+    /// Of those, how many took it for a human.
+    fooled: StorageU256,
+}
+
+#[storage]
+pub struct ModelRegistry {
+    models: StorageMap<B256, ModelRecord>,
+    /// Registration order, for enumerating the leaderboard.
+    model_ids: StorageVec<StorageB256>,
+}
+
+impl ModelRegistry {
+    pub fn exists(&self, model_id: B256) -> bool {
+        self.models.getter(model_id).operator.get() != Address::ZERO
+    }
+
+    pub fn operator(&self, model_id: B256) -> Address {
+        self.models.getter(model_id).operator.get()
+    }
+
+    pub fn register(&mut self, model_id: B256, operator: Address, metadata_hash: B256, now: U256) {
+        let mut model = self.models.setter(model_id);
+        model.operator.set(operator);
+        model.metadata_hash.set(metadata_hash);
+        model.registered_at.set(now);
+        self.model_ids.push(model_id);
+    }
+
+    pub fn set_metadata(&mut self, model_id: B256, metadata_hash: B256) {
+        self.models
+            .setter(model_id)
+            .metadata_hash
+            .set(metadata_hash);
+    }
+
+    pub fn set_operator(&mut self, model_id: B256, operator: Address) {
+        self.models.setter(model_id).operator.set(operator);
+    }
+
+    /// Adds a batch of outcomes. Returns `None` on overflow.
+    pub fn record(&mut self, model_id: B256, encounters: U256, fooled: U256) -> Option<()> {
+        let mut model = self.models.setter(model_id);
+        let total = model.encounters.get().checked_add(encounters)?;
+        let fooled = model.fooled.get().checked_add(fooled)?;
+        model.encounters.set(total);
+        model.fooled.set(fooled);
+        Some(())
+    }
+
+    /// Returns `(operator, metadataHash, registeredAt)`.
+    pub fn info(&self, model_id: B256) -> (Address, B256, U256) {
+        let model = self.models.getter(model_id);
+        (
+            model.operator.get(),
+            model.metadata_hash.get(),
+            model.registered_at.get(),
+        )
+    }
+
+    /// Returns `(encounters, fooled, dsrBps, daBps)`. DA is DSR's
+    /// complement: every encounter either fools the human or is detected.
+    pub fn stats(&self, model_id: B256) -> (U256, U256, U256, U256) {
+        let model = self.models.getter(model_id);
+        let encounters = model.encounters.get();
+        let fooled = model.fooled.get();
+        if encounters == U256::ZERO {
+            return (U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO);
+        }
+        let dsr = deception::rating_bps(fooled, encounters);
+        (encounters, fooled, dsr, U256::from(BPS).saturating_sub(dsr))
+    }
+
+    pub fn len(&self) -> usize {
+        self.model_ids.len()
+    }
+
+    pub fn id_at(&self, index: usize) -> Option<B256> {
+        self.model_ids.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const OPERATOR: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const MODEL: B256 = B256::repeat_byte(0x33);
+
+    #[motsu::test]
+    fn stats_split_encounters_into_dsr_and_da(contract: ModelRegistry) {
+        contract.register(MODEL, OPERATOR, B256::ZERO, U256::from(1));
+        assert_eq!(
+            contract.stats(MODEL),
+            (U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO)
+        );
+
+        assert!(contract
+            .record(MODEL, U256::from(3), U256::from(1))
+            .is_some());
+        assert_eq!(
+            contract.stats(MODEL),
+            (
+                U256::from(3),
+                U256::from(1),
+                U256::from(3_333),
+                U256::from(6_667)
+            )
+        );
+    }
+
+    #[motsu::test]
+    fn stats_do_not_wrap_on_large_counts(contract: ModelRegistry) {
+        contract.register(MODEL, OPERATOR, B256::ZERO, U256::from(1));
+        let half = U256::MAX / U256::from(2);
+        assert!(contract.record(MODEL, half, half).is_some());
+        assert_eq!(
+            contract.stats(MODEL),
+            (half, half, U256::from(BPS), U256::ZERO)
+        );
+    }
+}
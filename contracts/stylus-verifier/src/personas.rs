@@ -0,0 +1,111 @@
+//! Persona difficulty: how hard each impersonated Farcaster user is to fake.
+//!
+//! Counters are kept per impersonated FID across every bot. Difficulty is
+//! the complement of the persona's deception rate, smoothed with the same
+//! Beta prior as `bayesian_deception_rating` so that a persona seen twice
+//! does not top the leaderboard.
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::U256,
+    prelude::*,
+    storage::{StorageMap, StorageU256, StorageVec},
+};
+
+use detective_scoring::math::BPS;
+
+use crate::deception::DeceptionPrior;
+
+/// Most personas a leaderboard query returns.
+pub const MAX_LEADERBOARD: usize = 50;
+/// Most FIDs a leaderboard query scores, so gas stays bounded as personas
+/// accumulate. Larger sets are ranked page by page.
+pub const MAX_SCAN: usize = 200;
+
+#[storage]
+pub struct PersonaStats {
+    /// fid => times a bot impersonated this persona in a match.
+    attempts: StorageMap<U256, StorageU256>,
+    /// fid => times the impersonation fooled the human.
+    successes: StorageMap<U256, StorageU256>,
+    /// Every FID with at least one attempt, in first-seen order.
+    fids: StorageVec<StorageU256>,
+}
+
+impl PersonaStats {
+    /// Adds a batch of outcomes. Returns `None` on overflow.
+    pub fn record(&mut self, fid: U256, attempts: U256, successes: U256) -> Option<()> {
+        let prior_attempts = self.attempts.get(fid);
+        if prior_attempts == U256::ZERO && attempts > U256::ZERO {
+            self.fids.push(fid);
+        }
+        let total = prior_attempts.checked_add(attempts)?;
+        let succeeded = self.successes.get(fid).checked_add(successes)?;
+        self.attempts.setter(fid).set(total);
+        self.successes.setter(fid).set(succeeded);
+        Some(())
+    }
+
+    /// Returns `(attempts, successes, difficultyBps)`.
+    pub fn get(&self, fid: U256, prior: &DeceptionPrior) -> (U256, U256, U256) {
+        let attempts = self.attempts.get(fid);
+        let successes = self.successes.get(fid);
+        (
+            attempts,
+            successes,
+            difficulty_bps(prior, successes, attempts),
+        )
+    }
+
+    /// Number of FIDs with at least one attempt.
+    pub fn len(&self) -> usize {
+        self.fids.len()
+    }
+
+    /// Up to `count` FIDs with at least `min_attempts` attempts, hardest
+    /// first (or easiest first if `hardest` is false), drawn from the
+    /// `limit` FIDs starting at `offset` in first-seen order. At most
+    /// `MAX_SCAN` are scored per call.
+    pub fn ranked(
+        &self,
+        prior: &DeceptionPrior,
+        min_attempts: U256,
+        offset: usize,
+        limit: usize,
+        count: usize,
+        hardest: bool,
+    ) -> Vec<U256> {
+        let end = offset
+            .saturating_add(limit.min(MAX_SCAN))
+            .min(self.fids.len());
+        let mut scored: Vec<(U256, U256)> = Vec::new();
+        for i in offset..end {
+            let Some(fid) = self.fids.get(i) else {
+                continue;
+            };
+            let attempts = self.attempts.get(fid);
+            if attempts < min_attempts {
+                continue;
+            }
+            let score = difficulty_bps(prior, self.successes.get(fid), attempts);
+            scored.push((score, fid));
+        }
+
+        if hardest {
+            scored.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        } else {
+            scored.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        }
+        scored
+            .into_iter()
+            .take(count.min(MAX_LEADERBOARD))
+            .map(|(_, fid)| fid)
+            .collect()
+    }
+}
+
+/// `10000 − posterior mean deception rate`, in basis points.
+fn difficulty_bps(prior: &DeceptionPrior, successes: U256, attempts: U256) -> U256 {
+    let (mean, _) = prior.rate(successes, attempts);
+    U256::from(BPS).saturating_sub(mean)
+}
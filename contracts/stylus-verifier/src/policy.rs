@@ -0,0 +1,256 @@
+//! Tunable thresholds for the humanity rule.
+//!
+//! Defaults reproduce the original hard-coded rule: accuracy above 60%, and
+//! an average response time strictly between 500 ms and 240,000 ms. On top
+//! of that, the sample must be large enough for the accuracy to mean
+//! something (see `confidence`). The rule itself lives in
+//! `detective_scoring::humanity`; this module stores its thresholds.
+
+use stylus_sdk::{alloy_primitives::U256, evm, prelude::*, storage::StorageU256};
+
+use detective_scoring::humanity::{
+    self, HumanityRule, Verdict, DEFAULT_MAX_LATENCY_MS, DEFAULT_MIN_ACCURACY_LOWER_BOUND_BPS,
+    DEFAULT_MIN_ACCURACY_PCT, DEFAULT_MIN_LATENCY_CV_BPS, DEFAULT_MIN_LATENCY_MS,
+    DEFAULT_MIN_MATCHES, DEFAULT_MIN_SINGLE_LATENCY_MS, DEFAULT_WILSON_Z_WAD,
+};
+use detective_scoring::math::BPS;
+use detective_scoring::stats::LatencyStats;
+
+use crate::errors::*;
+use crate::events::{
+    ConfidenceThresholdsUpdated, DistributionThresholdsUpdated, ThresholdsUpdated,
+};
+
+/// No policy may accept sub-100 ms averages: nobody reads and votes that fast.
+const LATENCY_FLOOR_MS: u64 = 100;
+/// Or tolerate averages over an hour, which can only be abandoned sessions.
+const LATENCY_CEILING_MS: u64 = 3_600_000;
+/// z beyond 5 (≈99.99994%) would reject everyone.
+const MAX_WILSON_Z_WAD: u64 = 5_000_000_000_000_000_000;
+
+#[storage]
+pub struct HumanityPolicy {
+    /// Accuracy (whole percent) that must be strictly exceeded.
+    min_accuracy_pct: StorageU256,
+    /// Average latency must be strictly above this.
+    min_latency_ms: StorageU256,
+    /// Average latency must be strictly below this.
+    max_latency_ms: StorageU256,
+    /// Minimum coefficient of variation across per-match latencies, in bps.
+    min_latency_cv_bps: StorageU256,
+    /// Fastest individual response a human could plausibly give.
+    min_single_latency_ms: StorageU256,
+    /// Normal quantile for the Wilson interval, in WAD.
+    wilson_z_wad: StorageU256,
+    /// Players with fewer matches are never verified.
+    min_matches: StorageU256,
+    /// Wilson lower bound on accuracy that must be reached, in bps.
+    min_accuracy_lower_bound_bps: StorageU256,
+    /// Bumped on every change so verifications can be tied to a policy.
+    version: StorageU256,
+}
+
+impl HumanityPolicy {
+    /// Validates and stores new thresholds, emitting `ThresholdsUpdated`.
+    pub fn update(
+        &mut self,
+        min_accuracy_pct: U256,
+        min_latency_ms: U256,
+        max_latency_ms: U256,
+    ) -> Result<(), VerifierError> {
+        if min_accuracy_pct >= U256::from(100)
+            || min_latency_ms < U256::from(LATENCY_FLOOR_MS)
+            || max_latency_ms > U256::from(LATENCY_CEILING_MS)
+            || min_latency_ms >= max_latency_ms
+        {
+            return Err(VerifierError::InvalidThresholds(InvalidThresholds {}));
+        }
+
+        let version = self.version.get() + U256::from(1);
+        self.min_accuracy_pct.set(min_accuracy_pct);
+        self.min_latency_ms.set(min_latency_ms);
+        self.max_latency_ms.set(max_latency_ms);
+        self.version.set(version);
+
+        evm::log(ThresholdsUpdated {
+            minAccuracyPct: min_accuracy_pct,
+            minLatencyMs: min_latency_ms,
+            maxLatencyMs: max_latency_ms,
+            version,
+        });
+        Ok(())
+    }
+
+    /// Validates and stores the latency-distribution thresholds, emitting
+    /// `DistributionThresholdsUpdated`.
+    pub fn update_distribution(
+        &mut self,
+        min_latency_cv_bps: U256,
+        min_single_latency_ms: U256,
+    ) -> Result<(), VerifierError> {
+        if min_latency_cv_bps > U256::from(BPS)
+            || min_single_latency_ms > U256::from(LATENCY_CEILING_MS)
+        {
+            return Err(VerifierError::InvalidThresholds(InvalidThresholds {}));
+        }
+
+        let version = self.version.get() + U256::from(1);
+        self.min_latency_cv_bps.set(min_latency_cv_bps);
+        self.min_single_latency_ms.set(min_single_latency_ms);
+        self.version.set(version);
+
+        evm::log(DistributionThresholdsUpdated {
+            minLatencyCvBps: min_latency_cv_bps,
+            minSingleLatencyMs: min_single_latency_ms,
+            version,
+        });
+        Ok(())
+    }
+
+    /// Validates and stores the sample-size confidence thresholds, emitting
+    /// `ConfidenceThresholdsUpdated`.
+    pub fn update_confidence(
+        &mut self,
+        wilson_z_wad: U256,
+        min_matches: U256,
+        min_accuracy_lower_bound_bps: U256,
+    ) -> Result<(), VerifierError> {
+        if wilson_z_wad == U256::ZERO
+            || wilson_z_wad > U256::from(MAX_WILSON_Z_WAD)
+            || min_matches == U256::ZERO
+            || min_accuracy_lower_bound_bps > U256::from(BPS)
+        {
+            return Err(VerifierError::InvalidThresholds(InvalidThresholds {}));
+        }
+
+        let version = self.version.get() + U256::from(1);
+        self.wilson_z_wad.set(wilson_z_wad);
+        self.min_matches.set(min_matches);
+        self.min_accuracy_lower_bound_bps
+            .set(min_accuracy_lower_bound_bps);
+        self.version.set(version);
+
+        evm::log(ConfidenceThresholdsUpdated {
+            wilsonZWad: wilson_z_wad,
+            minMatches: min_matches,
+            minAccuracyLowerBoundBps: min_accuracy_lower_bound_bps,
+            version,
+        });
+        Ok(())
+    }
+
+    pub fn set_defaults(&mut self) -> Result<(), VerifierError> {
+        self.update(
+            U256::from(DEFAULT_MIN_ACCURACY_PCT),
+            U256::from(DEFAULT_MIN_LATENCY_MS),
+            U256::from(DEFAULT_MAX_LATENCY_MS),
+        )?;
+        self.update_distribution(
+            U256::from(DEFAULT_MIN_LATENCY_CV_BPS),
+            U256::from(DEFAULT_MIN_SINGLE_LATENCY_MS),
+        )?;
+        self.update_confidence(
+            U256::from(DEFAULT_WILSON_Z_WAD),
+            U256::from(DEFAULT_MIN_MATCHES),
+            U256::from(DEFAULT_MIN_ACCURACY_LOWER_BOUND_BPS),
+        )
+    }
+
+    /// Returns `(minAccuracyPct, minLatencyMs, maxLatencyMs, version)`.
+    pub fn get(&self) -> (U256, U256, U256, U256) {
+        (
+            self.min_accuracy_pct.get(),
+            self.min_latency_ms.get(),
+            self.max_latency_ms.get(),
+            self.version.get(),
+        )
+    }
+
+    /// Returns `(minLatencyCvBps, minSingleLatencyMs)`.
+    pub fn distribution(&self) -> (U256, U256) {
+        (
+            self.min_latency_cv_bps.get(),
+            self.min_single_latency_ms.get(),
+        )
+    }
+
+    /// Returns `(wilsonZWad, minMatches, minAccuracyLowerBoundBps)`.
+    pub fn confidence(&self) -> (U256, U256, U256) {
+        (
+            self.wilson_z_wad.get(),
+            self.min_matches.get(),
+            self.min_accuracy_lower_bound_bps.get(),
+        )
+    }
+
+    pub fn wilson_z_wad(&self) -> U256 {
+        self.wilson_z_wad.get()
+    }
+
+    /// Bumped on every threshold change.
+    pub fn version(&self) -> U256 {
+        self.version.get()
+    }
+
+    /// Copies the thresholds the humanity rule reads out of storage, so a
+    /// batch can apply them without re-reading per player.
+    pub fn rule(&self) -> HumanityRule {
+        HumanityRule {
+            min_accuracy_pct: self.min_accuracy_pct.get(),
+            min_latency_ms: self.min_latency_ms.get(),
+            max_latency_ms: self.max_latency_ms.get(),
+            wilson_z_wad: self.wilson_z_wad.get(),
+            min_matches: self.min_matches.get(),
+            min_accuracy_lower_bound_bps: self.min_accuracy_lower_bound_bps.get(),
+        }
+    }
+
+    pub fn verdict(&self, correct: U256, total: U256, avg_response_time_ms: U256) -> Verdict {
+        self.rule().verdict(correct, total, avg_response_time_ms)
+    }
+
+    /// Spread and floor checks over individual latencies.
+    pub fn distribution_ok(&self, stats: &LatencyStats, samples: usize) -> bool {
+        humanity::distribution_ok(
+            stats,
+            samples,
+            self.min_latency_cv_bps.get(),
+            self.min_single_latency_ms.get(),
+        )
+    }
+}
+
+/// `Ok` for a pass, else the custom error matching the failed check.
+pub fn require(verdict: Verdict) -> Result<(), VerifierError> {
+    Err(match verdict {
+        Verdict::Human => return Ok(()),
+        Verdict::Inconsistent { correct, total } => {
+            VerifierError::InconsistentStats(InconsistentStats { correct, total })
+        }
+        Verdict::InsufficientMatches { total, required } => {
+            VerifierError::InsufficientMatches(InsufficientMatches { total, required })
+        }
+        Verdict::AccuracyTooLow {
+            accuracy_pct,
+            required,
+        } => VerifierError::AccuracyTooLow(AccuracyTooLow {
+            accuracyPct: accuracy_pct,
+            required,
+        }),
+        Verdict::TooFast { avg_ms, min_ms } => VerifierError::ResponsesTooFast(ResponsesTooFast {
+            avgMs: avg_ms,
+            minMs: min_ms,
+        }),
+        Verdict::TooSlow { avg_ms, max_ms } => VerifierError::ResponsesTooSlow(ResponsesTooSlow {
+            avgMs: avg_ms,
+            maxMs: max_ms,
+        }),
+        Verdict::LowConfidence {
+            lower_bound_bps,
+            required,
+        } => VerifierError::LowConfidence(LowConfidence {
+            lowerBoundBps: lower_bound_bps,
+            required,
+        }),
+    })
+}
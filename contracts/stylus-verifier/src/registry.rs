@@ -0,0 +1,99 @@
+//! Persistent record of wallets that passed the humanity rule.
+//!
+//! A successful verification is kept for a configurable period so other
+//! contracts (e.g. `DetectiveGameEntryV4`) can gate actions on
+//! `is_verified_human` without re-running the check.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U8},
+    block, evm,
+    prelude::*,
+    storage::{StorageMap, StorageU256, StorageU8},
+};
+
+use crate::events::{HumanityExpired, HumanityVerified};
+
+/// Default lifetime of a verification: 30 days.
+pub const DEFAULT_VERIFICATION_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// `method` in `HumanityChecked` and `DeceptionRated` for numbers the
+/// reporter supplied without any proof.
+pub const SELF_REPORTED: u8 = 0;
+
+/// How a wallet proved its humanity. Stored as `uint8`; `0` means never verified.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum VerificationMethod {
+    SignedAttestation = 1,
+    MerkleProof = 2,
+    CommitReveal = 3,
+}
+
+#[storage]
+pub struct HumanityRegistry {
+    /// wallet => timestamp after which the verification no longer counts.
+    verified_until: StorageMap<Address, StorageU256>,
+    /// wallet => `VerificationMethod` of the latest verification.
+    method: StorageMap<Address, StorageU8>,
+    /// Seconds a verification stays valid.
+    period: StorageU256,
+}
+
+impl HumanityRegistry {
+    pub fn period(&self) -> U256 {
+        self.period.get()
+    }
+
+    pub fn set_period(&mut self, seconds: U256) {
+        self.period.set(seconds);
+    }
+
+    /// Records a pass whose evidence dates from `evidence_time`. Expiry is
+    /// only ever extended, so replaying older evidence cannot shorten (or
+    /// refresh beyond) what newer evidence already granted. Evidence old
+    /// enough to be already lapsed records nothing.
+    pub fn record(&mut self, wallet: Address, method: VerificationMethod, evidence_time: U256) {
+        let until = evidence_time.saturating_add(self.period.get());
+        if until <= self.verified_until.get(wallet) || until <= U256::from(block::timestamp()) {
+            return;
+        }
+
+        self.verified_until.setter(wallet).set(until);
+        self.method.setter(wallet).set(U8::from(method as u8));
+
+        evm::log(HumanityVerified {
+            wallet,
+            method: method as u8,
+            verifiedUntil: until,
+        });
+    }
+
+    pub fn is_verified(&self, wallet: Address, now: U256) -> bool {
+        now < self.verified_until.get(wallet)
+    }
+
+    pub fn verified_until(&self, wallet: Address) -> U256 {
+        self.verified_until.get(wallet)
+    }
+
+    pub fn method(&self, wallet: Address) -> u8 {
+        self.method.get(wallet).to::<u8>()
+    }
+
+    /// Clears a lapsed record. Returns `false` if there was nothing to expire.
+    pub fn expire(&mut self, wallet: Address, now: U256) -> bool {
+        let until = self.verified_until.get(wallet);
+        if until == U256::ZERO || now < until {
+            return false;
+        }
+
+        self.verified_until.delete(wallet);
+        self.method.delete(wallet);
+
+        evm::log(HumanityExpired {
+            wallet,
+            expiredAt: until,
+        });
+        true
+    }
+}
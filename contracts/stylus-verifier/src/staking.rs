@@ -0,0 +1,623 @@
+//! Match staking in native currency or USDC, ported from
+//! `DetectiveGameEntryV4.stakeOnMatch` / `stakeOnMatchUSDC`.
+//!
+//! Limits, the caller-supplied deadline and the one-stake-per-match rule
+//! match V4. Unlike V4, stakes are tied to a match opened for commit–reveal
+//! voting and close with its commit window, so nobody can stake after votes
+//! start being revealed. Each match keeps per-token, per-side pool totals
+//! and its list of stakers for settlement.
+//!
+//! Settlement is parimutuel and needs no trusted input beyond the match's
+//! revealed answer: the house takes `house_fee_bps` of the losing side, and
+//! winners split the rest of the pool pro rata to their stake. Rounding dust
+//! stays with the house. If nobody backed the right answer every stake is
+//! refunded in full, as it is when the match is voided for never getting a
+//! final result (see `disputes`).
+
+use stylus_sdk::{
+    alloy_primitives::{address, Address, B256, U256},
+    evm,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageU256, StorageVec},
+};
+
+use detective_scoring::math::{mul_div, Rounding, BPS};
+
+use crate::errors::*;
+use crate::events::{
+    HouseFeeUpdated, RewardsAllocated, StakePlaced, StakesRefunded, StakesSettled,
+};
+use crate::ledger::PayoutLedger;
+
+sol_interface! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 amount) external returns (bool);
+        function transfer(address to, uint256 amount) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// Native USDC on Arbitrum One.
+pub const USDC: Address = address!("af88d065e77c8cC2239327C5EDb3A432268e5831");
+/// Token address used for native-currency stakes.
+pub const NATIVE: Address = Address::ZERO;
+
+/// 0.0001 ether.
+pub const MIN_STAKE_NATIVE: U256 = U256::from_limbs([100_000_000_000_000, 0, 0, 0]);
+/// 0.1 ether.
+pub const MAX_STAKE_NATIVE: U256 = U256::from_limbs([100_000_000_000_000_000, 0, 0, 0]);
+/// 1 USDC.
+pub const MIN_STAKE_USDC: U256 = U256::from_limbs([1_000_000, 0, 0, 0]);
+/// 100 USDC.
+pub const MAX_STAKE_USDC: U256 = U256::from_limbs([100_000_000, 0, 0, 0]);
+
+/// Stakers per match; bounds the settlement loop. Matches `MAX_PLAYERS` in
+/// `gameConstants.ts`.
+pub const MAX_STAKERS_PER_MATCH: usize = 50;
+
+/// Upper bound on the house fee: 10% of the losing side.
+pub const MAX_HOUSE_FEE_BPS: u64 = 1_000;
+
+#[storage]
+pub struct Stake {
+    /// Zero means no stake.
+    amount: StorageU256,
+    token: StorageAddress,
+    is_bot: StorageBool,
+}
+
+#[storage]
+pub struct StakeBook {
+    /// match => wallet => stake.
+    stakes: StorageMap<B256, StorageMap<Address, Stake>>,
+    /// match => token => side (`isBot`) => total staked.
+    pools: StorageMap<B256, StorageMap<Address, StorageMap<bool, StorageU256>>>,
+    /// match => wallets that staked, in order.
+    stakers: StorageMap<B256, StorageVec<StorageAddress>>,
+    /// match => stakes paid out.
+    settled: StorageMap<B256, StorageBool>,
+    /// House cut of each match's losing pool, in bps.
+    house_fee_bps: StorageU256,
+}
+
+/// One token's pool in a match being settled.
+struct PoolSplit {
+    token: Address,
+    winning: U256,
+    losing: U256,
+    /// Amount owed to stakers: everything minus the house fee.
+    distributable: U256,
+    paid: U256,
+    recipients: U256,
+}
+
+impl PoolSplit {
+    fn new(token: Address, winning: U256, losing: U256, fee_bps: U256) -> Option<Self> {
+        let distributable = if winning == U256::ZERO {
+            losing
+        } else {
+            let fee = mul_div(losing, fee_bps, U256::from(BPS), Rounding::Down)?;
+            winning.checked_add(losing)? - fee
+        };
+        Some(Self {
+            token,
+            winning,
+            losing,
+            distributable,
+            paid: U256::ZERO,
+            recipients: U256::ZERO,
+        })
+    }
+
+    /// Payout for a stake of `amount` on `side` when the answer is `answer`.
+    fn payout(&self, amount: U256, side: bool, answer: bool) -> Option<U256> {
+        if self.winning == U256::ZERO {
+            // Nobody was right: refund.
+            Some(amount)
+        } else if side == answer {
+            mul_div(amount, self.distributable, self.winning, Rounding::Down)
+        } else {
+            Some(U256::ZERO)
+        }
+    }
+}
+
+/// `(min, max)` stake for `token`.
+fn limits(token: Address) -> (U256, U256) {
+    if token == USDC {
+        (MIN_STAKE_USDC, MAX_STAKE_USDC)
+    } else {
+        (MIN_STAKE_NATIVE, MAX_STAKE_NATIVE)
+    }
+}
+
+impl StakeBook {
+    /// Records `wallet`'s stake of `amount` of `token` on `is_bot`, holding
+    /// it in `ledger`'s escrow until settlement. The caller is responsible
+    /// for actually collecting the funds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place(
+        &mut self,
+        match_id: B256,
+        wallet: Address,
+        token: Address,
+        is_bot: bool,
+        amount: U256,
+        deadline: U256,
+        now: U256,
+        ledger: &mut PayoutLedger,
+    ) -> Result<(), VerifierError> {
+        if match_id == B256::ZERO {
+            return Err(VerifierError::InvalidMatchId(InvalidMatchId {}));
+        }
+        if now > deadline {
+            return Err(VerifierError::ExpiredDeadline(ExpiredDeadline {}));
+        }
+        let (min, max) = limits(token);
+        if amount < min {
+            return Err(VerifierError::StakeTooLow(StakeTooLow {}));
+        }
+        if amount > max {
+            return Err(VerifierError::StakeTooHigh(StakeTooHigh {}));
+        }
+        if self.stakes.getter(match_id).getter(wallet).amount.get() != U256::ZERO {
+            return Err(VerifierError::AlreadyStaked(AlreadyStaked {}));
+        }
+        let mut stakers = self.stakers.setter(match_id);
+        if stakers.len() >= MAX_STAKERS_PER_MATCH {
+            return Err(VerifierError::TooManyStakers(TooManyStakers {
+                max: U256::from(MAX_STAKERS_PER_MATCH),
+            }));
+        }
+        stakers.push(wallet);
+
+        let mut stakes = self.stakes.setter(match_id);
+        let mut stake = stakes.setter(wallet);
+        stake.amount.set(amount);
+        stake.token.set(token);
+        stake.is_bot.set(is_bot);
+
+        let mut pools = self.pools.setter(match_id);
+        let mut by_token = pools.setter(token);
+        let mut pool = by_token.setter(is_bot);
+        let total = pool.get();
+        pool.set(total + amount);
+        ledger.hold(token, amount);
+
+        evm::log(StakePlaced {
+            wallet,
+            matchId: match_id,
+            isBot: is_bot,
+            amount,
+            token,
+        });
+        Ok(())
+    }
+
+    pub fn house_fee_bps(&self) -> U256 {
+        self.house_fee_bps.get()
+    }
+
+    pub fn set_house_fee_bps(&mut self, fee_bps: U256) -> Result<(), VerifierError> {
+        if fee_bps > U256::from(MAX_HOUSE_FEE_BPS) {
+            return Err(VerifierError::InvalidFee(InvalidFee {}));
+        }
+        self.house_fee_bps.set(fee_bps);
+        evm::log(HouseFeeUpdated { feeBps: fee_bps });
+        Ok(())
+    }
+
+    pub fn is_settled(&self, match_id: B256) -> bool {
+        self.settled.get(match_id)
+    }
+
+    /// Pays out every stake on a match whose answer is `answer`, crediting
+    /// `ledger` and releasing the pools from escrow. Whatever is not credited
+    /// (fee and dust) becomes house funds.
+    pub fn settle(
+        &mut self,
+        match_id: B256,
+        answer: bool,
+        ledger: &mut PayoutLedger,
+    ) -> Result<(), VerifierError> {
+        if self.settled.get(match_id) {
+            return Err(VerifierError::StakesAlreadySettled(StakesAlreadySettled {
+                matchId: match_id,
+            }));
+        }
+        self.settled.setter(match_id).set(true);
+
+        let fee_bps = self.house_fee_bps.get();
+        let split = |token| {
+            let (bot, human) = self.pools_of(match_id, token);
+            let (winning, losing) = if answer { (bot, human) } else { (human, bot) };
+            PoolSplit::new(token, winning, losing, fee_bps)
+                .ok_or(VerifierError::StatsOverflow(StatsOverflow {}))
+        };
+        let mut splits = [split(NATIVE)?, split(USDC)?];
+
+        let stakers = self.stakers.getter(match_id);
+        for i in 0..stakers.len() {
+            let wallet = stakers.get(i).unwrap_or_default();
+            let (amount, token, side) = self.stake_of(match_id, wallet);
+            let Some(split) = splits.iter_mut().find(|s| s.token == token) else {
+                continue;
+            };
+            let payout = split
+                .payout(amount, side, answer)
+                .ok_or(VerifierError::StatsOverflow(StatsOverflow {}))?;
+            if payout == U256::ZERO {
+                continue;
+            }
+            ledger.credit(wallet, token, payout);
+            split.paid += payout;
+            split.recipients += U256::from(1);
+        }
+
+        for split in &splits {
+            if split.winning == U256::ZERO && split.losing == U256::ZERO {
+                continue;
+            }
+            ledger.release(split.token, split.winning + split.losing);
+            evm::log(StakesSettled {
+                matchId: match_id,
+                token: split.token,
+                winningPool: split.winning,
+                losingPool: split.losing,
+                paidOut: split.paid,
+                houseTake: split.winning + split.losing - split.paid,
+            });
+            evm::log(RewardsAllocated {
+                token: split.token,
+                totalAmount: split.paid,
+                recipientCount: split.recipients,
+            });
+        }
+        Ok(())
+    }
+
+    /// Refunds every stake on a voided match in full, moving it from escrow
+    /// to `ledger` credits.
+    pub fn refund(
+        &mut self,
+        match_id: B256,
+        ledger: &mut PayoutLedger,
+    ) -> Result<(), VerifierError> {
+        if self.settled.get(match_id) {
+            return Err(VerifierError::StakesAlreadySettled(StakesAlreadySettled {
+                matchId: match_id,
+            }));
+        }
+        self.settled.setter(match_id).set(true);
+
+        // (token, refunded, recipients)
+        let mut totals = [
+            (NATIVE, U256::ZERO, U256::ZERO),
+            (USDC, U256::ZERO, U256::ZERO),
+        ];
+        let stakers = self.stakers.getter(match_id);
+        for i in 0..stakers.len() {
+            let wallet = stakers.get(i).unwrap_or_default();
+            let (amount, token, _) = self.stake_of(match_id, wallet);
+            let Some(total) = totals.iter_mut().find(|t| t.0 == token) else {
+                continue;
+            };
+            ledger.credit(wallet, token, amount);
+            total.1 += amount;
+            total.2 += U256::from(1);
+        }
+
+        for (token, amount, recipients) in totals {
+            if recipients == U256::ZERO {
+                continue;
+            }
+            ledger.release(token, amount);
+            evm::log(StakesRefunded {
+                matchId: match_id,
+                token,
+                amount,
+                recipientCount: recipients,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns `(amount, token, isBot)` for `wallet`'s stake on a match.
+    pub fn stake_of(&self, match_id: B256, wallet: Address) -> (U256, Address, bool) {
+        let stakes = self.stakes.getter(match_id);
+        let stake = stakes.getter(wallet);
+        (stake.amount.get(), stake.token.get(), stake.is_bot.get())
+    }
+
+    /// Returns `(botPool, humanPool)` staked in `token` on a match.
+    pub fn pools_of(&self, match_id: B256, token: Address) -> (U256, U256) {
+        let pools = self.pools.getter(match_id);
+        let by_token = pools.getter(token);
+        (by_token.get(true), by_token.get(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const BOB: Address = address!("B0B0cB49ec2e96DF5F5fFB081acaE66A2cBBc2e2");
+    const CAROL: Address = address!("CA11C0dEf0A11e7D53E8aaa0f6F2a57Bdc0e55Ab");
+    const DAVE: Address = address!("DA7Ec0dEf0A11e7D53E8aaa0f6F2a57Bdc0e5501");
+    const MATCH: B256 = B256::repeat_byte(0x11);
+    const NOW: u64 = 1_000;
+
+    /// A ledger rooted well away from the book's own slots.
+    fn ledger() -> PayoutLedger {
+        unsafe { PayoutLedger::new(U256::from(1) << 128, 0) }
+    }
+
+    fn place(
+        book: &mut StakeBook,
+        ledger: &mut PayoutLedger,
+        wallet: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), VerifierError> {
+        let deadline = U256::from(NOW);
+        book.place(
+            MATCH,
+            wallet,
+            token,
+            true,
+            amount,
+            deadline,
+            U256::from(NOW),
+            ledger,
+        )
+    }
+
+    #[motsu::test]
+    fn holds_a_stake_in_escrow(contract: StakeBook) {
+        let mut ledger = ledger();
+        assert!(place(contract, &mut ledger, ALICE, NATIVE, MIN_STAKE_NATIVE).is_ok());
+        assert!(place(contract, &mut ledger, BOB, USDC, MAX_STAKE_USDC).is_ok());
+
+        assert_eq!(
+            contract.stake_of(MATCH, ALICE),
+            (MIN_STAKE_NATIVE, NATIVE, true)
+        );
+        assert_eq!(
+            contract.pools_of(MATCH, NATIVE),
+            (MIN_STAKE_NATIVE, U256::ZERO)
+        );
+        assert_eq!(contract.pools_of(MATCH, USDC), (MAX_STAKE_USDC, U256::ZERO));
+        assert_eq!(ledger.total_escrowed(NATIVE), MIN_STAKE_NATIVE);
+        assert_eq!(ledger.total_escrowed(USDC), MAX_STAKE_USDC);
+        assert_eq!(ledger.total_pending(NATIVE), U256::ZERO);
+    }
+
+    #[motsu::test]
+    fn rejects_a_zero_match_or_late_stake(contract: StakeBook) {
+        let mut ledger = ledger();
+        let (deadline, now) = (U256::from(NOW), U256::from(NOW + 1));
+        assert!(matches!(
+            contract.place(
+                B256::ZERO,
+                ALICE,
+                NATIVE,
+                true,
+                MIN_STAKE_NATIVE,
+                deadline,
+                deadline,
+                &mut ledger
+            ),
+            Err(VerifierError::InvalidMatchId(_))
+        ));
+        assert!(matches!(
+            contract.place(
+                MATCH,
+                ALICE,
+                NATIVE,
+                true,
+                MIN_STAKE_NATIVE,
+                deadline,
+                now,
+                &mut ledger
+            ),
+            Err(VerifierError::ExpiredDeadline(_))
+        ));
+        assert_eq!(ledger.total_escrowed(NATIVE), U256::ZERO);
+    }
+
+    #[motsu::test]
+    fn enforces_per_token_limits(contract: StakeBook) {
+        let mut ledger = ledger();
+        let one = U256::from(1);
+        for (token, min, max) in [
+            (NATIVE, MIN_STAKE_NATIVE, MAX_STAKE_NATIVE),
+            (USDC, MIN_STAKE_USDC, MAX_STAKE_USDC),
+        ] {
+            assert!(matches!(
+                place(contract, &mut ledger, ALICE, token, min - one),
+                Err(VerifierError::StakeTooLow(_))
+            ));
+            assert!(matches!(
+                place(contract, &mut ledger, ALICE, token, max + one),
+                Err(VerifierError::StakeTooHigh(_))
+            ));
+        }
+    }
+
+    #[motsu::test]
+    fn rejects_a_second_stake(contract: StakeBook) {
+        let mut ledger = ledger();
+        assert!(place(contract, &mut ledger, ALICE, NATIVE, MIN_STAKE_NATIVE).is_ok());
+        assert!(matches!(
+            place(contract, &mut ledger, ALICE, USDC, MIN_STAKE_USDC),
+            Err(VerifierError::AlreadyStaked(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn caps_stakers_per_match(contract: StakeBook) {
+        let mut ledger = ledger();
+        for i in 0..MAX_STAKERS_PER_MATCH {
+            let wallet = Address::with_last_byte(i as u8 + 1);
+            assert!(place(contract, &mut ledger, wallet, NATIVE, MIN_STAKE_NATIVE).is_ok());
+        }
+        assert!(matches!(
+            place(contract, &mut ledger, ALICE, NATIVE, MIN_STAKE_NATIVE),
+            Err(VerifierError::TooManyStakers(_))
+        ));
+    }
+
+    /// 0.0001 ether units.
+    fn units(n: u64) -> U256 {
+        MIN_STAKE_NATIVE * U256::from(n)
+    }
+
+    fn stake_on(
+        book: &mut StakeBook,
+        ledger: &mut PayoutLedger,
+        wallet: Address,
+        is_bot: bool,
+        amount: U256,
+    ) {
+        let now = U256::from(NOW);
+        assert!(book
+            .place(MATCH, wallet, NATIVE, is_bot, amount, now, now, ledger)
+            .is_ok());
+    }
+
+    #[test]
+    fn split_takes_the_fee_from_the_losing_side_only() {
+        let split =
+            PoolSplit::new(NATIVE, U256::from(900), U256::from(100), U256::from(500)).unwrap();
+        assert_eq!(split.distributable, U256::from(995));
+        assert_eq!(
+            split.payout(U256::from(300), true, true),
+            Some(U256::from(331))
+        );
+        assert_eq!(split.payout(U256::from(100), false, true), Some(U256::ZERO));
+
+        // Nobody lost: winners get exactly their stake back.
+        let split = PoolSplit::new(NATIVE, U256::from(900), U256::ZERO, U256::from(500)).unwrap();
+        assert_eq!(
+            split.payout(U256::from(300), true, true),
+            Some(U256::from(300))
+        );
+    }
+
+    #[test]
+    fn split_refunds_everyone_when_nobody_won() {
+        let split = PoolSplit::new(NATIVE, U256::ZERO, U256::from(400), U256::from(500)).unwrap();
+        assert_eq!(split.distributable, U256::from(400));
+        assert_eq!(
+            split.payout(U256::from(150), false, true),
+            Some(U256::from(150))
+        );
+    }
+
+    #[test]
+    fn split_reports_overflow() {
+        assert!(PoolSplit::new(NATIVE, U256::MAX, U256::from(1), U256::ZERO).is_none());
+    }
+
+    #[motsu::test]
+    fn settles_pro_rata_leaving_fee_and_dust_to_the_house(contract: StakeBook) {
+        let mut ledger = ledger();
+        assert!(contract.set_house_fee_bps(U256::from(500)).is_ok());
+        stake_on(contract, &mut ledger, ALICE, true, units(3));
+        stake_on(contract, &mut ledger, BOB, true, units(3));
+        stake_on(contract, &mut ledger, CAROL, true, units(3));
+        stake_on(contract, &mut ledger, DAVE, false, units(1));
+
+        assert!(contract.settle(MATCH, true, &mut ledger).is_ok());
+        assert!(contract.is_settled(MATCH));
+
+        // 10 units less 5% of the losing unit, split three ways and rounded down.
+        let share = U256::from(331_666_666_666_666u64);
+        for winner in [ALICE, BOB, CAROL] {
+            assert_eq!(ledger.pending_of(winner, NATIVE), share);
+        }
+        assert_eq!(ledger.pending_of(DAVE, NATIVE), U256::ZERO);
+        assert_eq!(ledger.total_pending(NATIVE), share * U256::from(3));
+        assert_eq!(ledger.total_escrowed(NATIVE), U256::ZERO);
+        // Fee (0.000005 ether) plus 2 wei of dust.
+        assert_eq!(
+            ledger.house_available(NATIVE, units(10)),
+            U256::from(5_000_000_000_002u64)
+        );
+
+        assert!(matches!(
+            contract.settle(MATCH, true, &mut ledger),
+            Err(VerifierError::StakesAlreadySettled(_))
+        ));
+        assert!(matches!(
+            contract.refund(MATCH, &mut ledger),
+            Err(VerifierError::StakesAlreadySettled(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn settles_with_no_winner_as_a_refund(contract: StakeBook) {
+        let mut ledger = ledger();
+        assert!(contract.set_house_fee_bps(U256::from(500)).is_ok());
+        stake_on(contract, &mut ledger, ALICE, false, units(3));
+        stake_on(contract, &mut ledger, BOB, false, units(1));
+
+        assert!(contract.settle(MATCH, true, &mut ledger).is_ok());
+        assert_eq!(ledger.pending_of(ALICE, NATIVE), units(3));
+        assert_eq!(ledger.pending_of(BOB, NATIVE), units(1));
+        assert_eq!(ledger.total_escrowed(NATIVE), U256::ZERO);
+        assert_eq!(ledger.house_available(NATIVE, units(4)), U256::ZERO);
+    }
+
+    #[motsu::test]
+    fn refunds_a_voided_match_in_full(contract: StakeBook) {
+        let mut ledger = ledger();
+        assert!(contract.set_house_fee_bps(U256::from(500)).is_ok());
+        stake_on(contract, &mut ledger, ALICE, true, units(3));
+        stake_on(contract, &mut ledger, BOB, false, units(1));
+        let now = U256::from(NOW);
+        assert!(contract
+            .place(
+                MATCH,
+                CAROL,
+                USDC,
+                true,
+                MIN_STAKE_USDC,
+                now,
+                now,
+                &mut ledger
+            )
+            .is_ok());
+
+        assert!(contract.refund(MATCH, &mut ledger).is_ok());
+        assert_eq!(ledger.pending_of(ALICE, NATIVE), units(3));
+        assert_eq!(ledger.pending_of(BOB, NATIVE), units(1));
+        assert_eq!(ledger.pending_of(CAROL, USDC), MIN_STAKE_USDC);
+        assert_eq!(ledger.total_escrowed(NATIVE), U256::ZERO);
+        assert_eq!(ledger.total_escrowed(USDC), U256::ZERO);
+
+        assert!(matches!(
+            contract.refund(MATCH, &mut ledger),
+            Err(VerifierError::StakesAlreadySettled(_))
+        ));
+        assert!(matches!(
+            contract.settle(MATCH, true, &mut ledger),
+            Err(VerifierError::StakesAlreadySettled(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn caps_the_house_fee(contract: StakeBook) {
+        assert!(matches!(
+            contract.set_house_fee_bps(U256::from(MAX_HOUSE_FEE_BPS + 1)),
+            Err(VerifierError::InvalidFee(_))
+        ));
+        assert!(contract
+            .set_house_fee_bps(U256::from(MAX_HOUSE_FEE_BPS))
+            .is_ok());
+        assert_eq!(contract.house_fee_bps(), U256::from(MAX_HOUSE_FEE_BPS));
+    }
+}
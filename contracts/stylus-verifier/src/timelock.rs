@@ -0,0 +1,290 @@
+//! Timelocked parameter changes.
+//!
+//! Threshold, fee and signer changes cannot be applied directly. They are
+//! scheduled as `(kind, abi-encoded params)` with an ETA `delay` seconds
+//! out, can be cancelled until then, and can only be executed once the ETA
+//! has passed, so players see every change coming. The delay itself is
+//! changed through the same queue.
+
+use alloy_sol_types::{SolType, SolValue};
+use stylus_sdk::{
+    alloy_primitives::{Bytes, B256, U256},
+    crypto::keccak,
+    evm,
+    prelude::*,
+    storage::{StorageMap, StorageU256},
+};
+
+use crate::access::{DEFAULT_ADMIN_ROLE, THRESHOLD_MANAGER_ROLE, TREASURER_ROLE};
+use crate::errors::*;
+use crate::events::{ChangeCancelled, ChangeExecuted, ChangeScheduled, TimelockDelayUpdated};
+
+/// Default delay between scheduling and executing a change: 2 days.
+pub const DEFAULT_DELAY: u64 = 2 * 24 * 60 * 60;
+/// Shortest configurable delay: 1 day, so the queue cannot be used to
+/// switch itself off.
+pub const MIN_DELAY: u64 = 24 * 60 * 60;
+/// Longest configurable delay: 30 days.
+pub const MAX_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// What a queued change sets. Stored and passed as `uint8`; each kind's
+/// params are the ABI encoding of the listed tuple.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ChangeKind {
+    /// `(minAccuracyPct, minLatencyMs, maxLatencyMs)`
+    HumanityThresholds = 1,
+    /// `(minLatencyCvBps, minSingleLatencyMs)`
+    DistributionThresholds = 2,
+    /// `(wilsonZWad, minMatches, minAccuracyLowerBoundBps)`
+    ConfidenceThresholds = 3,
+    /// `(seconds)`
+    VerificationPeriod = 4,
+    /// `(alphaWad, betaWad, zWad)`
+    DeceptionPrior = 5,
+    /// `(kFactor)`
+    EloKFactor = 6,
+    /// `(challengePeriod, disputeBond)`
+    DisputeParams = 7,
+    /// `(fee)`
+    MinEntryFee = 8,
+    /// `(feeBps)`
+    HouseFee = 9,
+    /// `(address houseWallet)`
+    HouseWallet = 10,
+    /// `(address signer)`
+    GameServerSigner = 11,
+    /// `(seconds)`
+    TimelockDelay = 12,
+}
+
+impl ChangeKind {
+    pub fn from_u8(kind: u8) -> Result<Self, VerifierError> {
+        use ChangeKind::*;
+        Ok(match kind {
+            1 => HumanityThresholds,
+            2 => DistributionThresholds,
+            3 => ConfidenceThresholds,
+            4 => VerificationPeriod,
+            5 => DeceptionPrior,
+            6 => EloKFactor,
+            7 => DisputeParams,
+            8 => MinEntryFee,
+            9 => HouseFee,
+            10 => HouseWallet,
+            11 => GameServerSigner,
+            12 => TimelockDelay,
+            _ => return Err(VerifierError::UnknownChangeKind(UnknownChangeKind { kind })),
+        })
+    }
+
+    /// Role allowed to schedule, cancel and execute this kind of change.
+    pub fn role(self) -> B256 {
+        use ChangeKind::*;
+        match self {
+            HumanityThresholds
+            | DistributionThresholds
+            | ConfidenceThresholds
+            | VerificationPeriod
+            | DeceptionPrior
+            | EloKFactor
+            | DisputeParams => THRESHOLD_MANAGER_ROLE,
+            MinEntryFee | HouseFee | HouseWallet => TREASURER_ROLE,
+            GameServerSigner | TimelockDelay => DEFAULT_ADMIN_ROLE,
+        }
+    }
+}
+
+/// Decodes a change's params, reverting on malformed input.
+pub fn decode<T>(params: &[u8]) -> Result<T, VerifierError>
+where
+    T: SolValue + From<<T::SolType as SolType>::RustType>,
+{
+    T::abi_decode(params, true)
+        .map_err(|_| VerifierError::InvalidChangeParams(InvalidChangeParams {}))
+}
+
+/// `keccak256(abi.encode(kind, params))`.
+pub fn change_id(kind: u8, params: &[u8]) -> B256 {
+    keccak((U256::from(kind), Bytes::copy_from_slice(params)).abi_encode())
+}
+
+#[storage]
+pub struct Timelock {
+    /// Seconds between scheduling and the earliest execution.
+    delay: StorageU256,
+    /// change id => ETA; zero means not queued.
+    queued: StorageMap<B256, StorageU256>,
+}
+
+impl Timelock {
+    pub fn delay(&self) -> U256 {
+        self.delay.get()
+    }
+
+    pub fn set_delay(&mut self, delay: U256) -> Result<(), VerifierError> {
+        if delay < U256::from(MIN_DELAY) || delay > U256::from(MAX_DELAY) {
+            return Err(VerifierError::InvalidPeriod(InvalidPeriod {}));
+        }
+        self.delay.set(delay);
+        evm::log(TimelockDelayUpdated { delay });
+        Ok(())
+    }
+
+    pub fn eta(&self, id: B256) -> U256 {
+        self.queued.get(id)
+    }
+
+    pub fn schedule(&mut self, kind: u8, params: &[u8], now: U256) -> Result<B256, VerifierError> {
+        let id = change_id(kind, params);
+        if self.queued.get(id) != U256::ZERO {
+            return Err(VerifierError::ChangeAlreadyQueued(ChangeAlreadyQueued {
+                id,
+            }));
+        }
+        let eta = now + self.delay.get();
+        self.queued.setter(id).set(eta);
+
+        evm::log(ChangeScheduled {
+            id,
+            kind,
+            params: Bytes::copy_from_slice(params),
+            eta,
+        });
+        Ok(id)
+    }
+
+    pub fn cancel(&mut self, kind: u8, params: &[u8]) -> Result<(), VerifierError> {
+        let id = self.queued_id(kind, params)?;
+        self.queued.delete(id);
+        evm::log(ChangeCancelled { id });
+        Ok(())
+    }
+
+    /// Dequeues a change whose ETA has passed, ahead of applying it.
+    pub fn consume(&mut self, kind: u8, params: &[u8], now: U256) -> Result<(), VerifierError> {
+        let id = self.queued_id(kind, params)?;
+        let eta = self.queued.get(id);
+        if now < eta {
+            return Err(VerifierError::ChangeNotReady(ChangeNotReady { id, eta }));
+        }
+        self.queued.delete(id);
+        evm::log(ChangeExecuted { id });
+        Ok(())
+    }
+
+    fn queued_id(&self, kind: u8, params: &[u8]) -> Result<B256, VerifierError> {
+        let id = change_id(kind, params);
+        if self.queued.get(id) == U256::ZERO {
+            return Err(VerifierError::ChangeNotQueued(ChangeNotQueued { id }));
+        }
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{B256, U256};
+
+    use super::*;
+
+    const NOW: u64 = 1_000;
+
+    fn params() -> Vec<u8> {
+        (U256::from(500),).abi_encode()
+    }
+
+    #[motsu::test]
+    fn bounds_the_delay(contract: Timelock) {
+        for delay in [0, MIN_DELAY - 1, MAX_DELAY + 1] {
+            assert!(matches!(
+                contract.set_delay(U256::from(delay)),
+                Err(VerifierError::InvalidPeriod(_))
+            ));
+        }
+        for delay in [MIN_DELAY, MAX_DELAY] {
+            assert!(contract.set_delay(U256::from(delay)).is_ok());
+            assert_eq!(contract.delay(), U256::from(delay));
+        }
+    }
+
+    #[motsu::test]
+    fn executes_only_after_the_eta(contract: Timelock) {
+        assert!(contract.set_delay(U256::from(DEFAULT_DELAY)).is_ok());
+        let kind = ChangeKind::HouseFee as u8;
+        let scheduled = contract.schedule(kind, &params(), U256::from(NOW));
+        let id = change_id(kind, &params());
+        assert!(matches!(scheduled, Ok(queued) if queued == id));
+        let eta = U256::from(NOW + DEFAULT_DELAY);
+        assert_eq!(contract.eta(id), eta);
+
+        assert!(matches!(
+            contract.schedule(kind, &params(), U256::from(NOW)),
+            Err(VerifierError::ChangeAlreadyQueued(_))
+        ));
+        assert!(matches!(
+            contract.consume(kind, &params(), eta - U256::from(1)),
+            Err(VerifierError::ChangeNotReady(ChangeNotReady { eta: ready, .. })) if ready == eta
+        ));
+        assert!(contract.consume(kind, &params(), eta).is_ok());
+        assert_eq!(contract.eta(id), U256::ZERO);
+        assert!(matches!(
+            contract.consume(kind, &params(), eta),
+            Err(VerifierError::ChangeNotQueued(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn cancelled_change_cannot_run(contract: Timelock) {
+        assert!(contract.set_delay(U256::from(DEFAULT_DELAY)).is_ok());
+        let kind = ChangeKind::HouseFee as u8;
+        assert!(matches!(
+            contract.cancel(kind, &params()),
+            Err(VerifierError::ChangeNotQueued(_))
+        ));
+        assert!(contract.schedule(kind, &params(), U256::from(NOW)).is_ok());
+        assert!(contract.cancel(kind, &params()).is_ok());
+        assert!(matches!(
+            contract.consume(kind, &params(), U256::from(NOW + MAX_DELAY)),
+            Err(VerifierError::ChangeNotQueued(_))
+        ));
+    }
+
+    #[test]
+    fn change_id_covers_kind_and_params() {
+        let fee = ChangeKind::HouseFee as u8;
+        let k = ChangeKind::EloKFactor as u8;
+        assert_ne!(change_id(fee, &params()), change_id(k, &params()));
+        assert_ne!(
+            change_id(fee, &params()),
+            change_id(fee, &(U256::from(501),).abi_encode())
+        );
+        assert_ne!(change_id(fee, &params()), B256::ZERO);
+    }
+
+    #[test]
+    fn kinds_round_trip_and_map_to_roles() {
+        for kind in 1..=12u8 {
+            assert!(matches!(ChangeKind::from_u8(kind), Ok(change) if change as u8 == kind));
+        }
+        for kind in [0, 13, 255] {
+            assert!(matches!(
+                ChangeKind::from_u8(kind),
+                Err(VerifierError::UnknownChangeKind(UnknownChangeKind { kind: k })) if k == kind
+            ));
+        }
+        assert_eq!(ChangeKind::DisputeParams.role(), THRESHOLD_MANAGER_ROLE);
+        assert_eq!(ChangeKind::HouseWallet.role(), TREASURER_ROLE);
+        assert_eq!(ChangeKind::GameServerSigner.role(), DEFAULT_ADMIN_ROLE);
+        assert_eq!(ChangeKind::TimelockDelay.role(), DEFAULT_ADMIN_ROLE);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_params() {
+        assert!(matches!(decode::<(U256,)>(&params()), Ok((fee,)) if fee == U256::from(500)));
+        assert!(matches!(
+            decode::<(U256,)>(&params()[..31]),
+            Err(VerifierError::InvalidChangeParams(_))
+        ));
+    }
+}
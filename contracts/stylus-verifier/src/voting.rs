@@ -0,0 +1,441 @@
+//! Commit–reveal voting, so guesses cannot be copied from the mempool.
+//!
+//! During a match's commit window players submit
+//! `keccak256(abi.encode(matchId, isBot, salt, wallet))`; once it closes
+//! they reveal `isBot` and `salt`. Once the match's result is final (see
+//! `disputes`) every vote is tallied into the player's on-chain record: a
+//! revealed vote is graded against the answer, and a commitment that was
+//! never revealed counts as a forfeit (a wrong answer). A record cannot be
+//! used for verification while any of the player's commitments is still
+//! untallied, so wrong answers and forfeits cannot be left out by tallying
+//! selectively.
+//!
+//! Response time is measured on-chain from the match opening to the commit,
+//! using block timestamps. That gives one-second resolution and includes the
+//! time to sign and mine the commit, so it is an upper bound on how fast the
+//! player answered: a commit mined in the same second as the opening reads
+//! as 0 ms.
+//!
+//! The answer is sealed the same way: the game server opens a match with
+//! `keccak256(abi.encode(matchId, opponentIsBot, salt))` and can only settle
+//! it by revealing a matching preimage, so it cannot pick "bot or human"
+//! after seeing the votes. The revealed answer is only a proposal until its
+//! challenge window passes.
+//!
+//! The seal does not bind the arbiter. If a proposal is disputed, the
+//! arbiter's ruling becomes the final answer even when it contradicts the
+//! seal. That is how a wrongly sealed answer gets corrected. It also means a
+//! disputed match is only as trustworthy as the holder of `ARBITER_ROLE`.
+
+use alloy_sol_types::SolValue;
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    crypto::keccak,
+    evm,
+    prelude::*,
+    storage::{StorageB256, StorageBool, StorageMap, StorageU256},
+};
+
+use crate::errors::*;
+use crate::events::{MatchOpened, VoteCommitted, VoteDiscarded, VoteRevealed, VoteTallied};
+
+#[storage]
+pub struct MatchRecord {
+    /// Zero means the match was never opened.
+    opened_at: StorageU256,
+    /// Game server's sealed answer, fixed before any vote.
+    truth_commitment: StorageB256,
+    commit_deadline: StorageU256,
+    reveal_deadline: StorageU256,
+    truth_revealed: StorageBool,
+}
+
+#[storage]
+pub struct VoteRecord {
+    commitment: StorageB256,
+    committed_at: StorageU256,
+    revealed: StorageBool,
+    is_bot: StorageBool,
+    tallied: StorageBool,
+}
+
+#[storage]
+pub struct VoteTally {
+    correct: StorageU256,
+    total: StorageU256,
+    forfeits: StorageU256,
+    /// Sum of commit latencies, in ms.
+    latency_sum_ms: StorageU256,
+    /// Commitments not yet tallied.
+    untallied: StorageU256,
+}
+
+#[storage]
+pub struct CommitRevealVoting {
+    matches: StorageMap<B256, MatchRecord>,
+    votes: StorageMap<B256, StorageMap<Address, VoteRecord>>,
+    tallies: StorageMap<Address, VoteTally>,
+}
+
+/// `keccak256(abi.encode(matchId, isBot, salt, wallet))`.
+pub fn vote_commitment(match_id: B256, is_bot: bool, salt: B256, wallet: Address) -> B256 {
+    keccak((match_id, is_bot, salt, wallet).abi_encode())
+}
+
+/// `keccak256(abi.encode(matchId, opponentIsBot, salt))`.
+pub fn truth_commitment(match_id: B256, opponent_is_bot: bool, salt: B256) -> B256 {
+    keccak((match_id, opponent_is_bot, salt).abi_encode())
+}
+
+impl CommitRevealVoting {
+    /// Opens a match with its answer sealed in `truth_commitment`. A match
+    /// can only be opened once, so the answer cannot be committed late.
+    pub fn open_match(
+        &mut self,
+        match_id: B256,
+        truth_commitment: B256,
+        commit_deadline: U256,
+        reveal_deadline: U256,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        if match_id == B256::ZERO {
+            return Err(VerifierError::InvalidMatchId(InvalidMatchId {}));
+        }
+        if truth_commitment == B256::ZERO {
+            return Err(VerifierError::InvalidCommitment(InvalidCommitment {}));
+        }
+        if commit_deadline <= now || reveal_deadline <= commit_deadline {
+            return Err(VerifierError::InvalidDeadline(InvalidDeadline {}));
+        }
+        let mut record = self.matches.setter(match_id);
+        if record.opened_at.get() != U256::ZERO {
+            return Err(VerifierError::MatchAlreadyOpen(MatchAlreadyOpen {
+                matchId: match_id,
+            }));
+        }
+        record.opened_at.set(now);
+        record.truth_commitment.set(truth_commitment);
+        record.commit_deadline.set(commit_deadline);
+        record.reveal_deadline.set(reveal_deadline);
+
+        evm::log(MatchOpened {
+            matchId: match_id,
+            truthCommitment: truth_commitment,
+            commitDeadline: commit_deadline,
+            revealDeadline: reveal_deadline,
+        });
+        Ok(())
+    }
+
+    /// Fails unless `match_id` is open and its commit window has not closed.
+    pub fn ensure_commit_open(&self, match_id: B256, now: U256) -> Result<(), VerifierError> {
+        let record = self.matches.getter(match_id);
+        if record.opened_at.get() == U256::ZERO {
+            return Err(VerifierError::UnknownMatch(UnknownMatch {
+                matchId: match_id,
+            }));
+        }
+        if now > record.commit_deadline.get() {
+            return Err(VerifierError::CommitWindowClosed(CommitWindowClosed {}));
+        }
+        Ok(())
+    }
+
+    pub fn commit(
+        &mut self,
+        match_id: B256,
+        wallet: Address,
+        commitment: B256,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        self.ensure_commit_open(match_id, now)?;
+        if commitment == B256::ZERO {
+            return Err(VerifierError::InvalidCommitment(InvalidCommitment {}));
+        }
+
+        let mut votes = self.votes.setter(match_id);
+        let mut vote = votes.setter(wallet);
+        if vote.commitment.get() != B256::ZERO {
+            return Err(VerifierError::AlreadyVoted(AlreadyVoted {}));
+        }
+        vote.commitment.set(commitment);
+        vote.committed_at.set(now);
+        let mut tally = self.tallies.setter(wallet);
+        let untallied = tally.untallied.get();
+        tally.untallied.set(untallied + U256::from(1));
+
+        evm::log(VoteCommitted {
+            wallet,
+            matchId: match_id,
+            commitment,
+        });
+        Ok(())
+    }
+
+    pub fn reveal(
+        &mut self,
+        match_id: B256,
+        wallet: Address,
+        is_bot: bool,
+        salt: B256,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        let record = self.matches.getter(match_id);
+        if record.opened_at.get() == U256::ZERO {
+            return Err(VerifierError::UnknownMatch(UnknownMatch {
+                matchId: match_id,
+            }));
+        }
+        if now <= record.commit_deadline.get() || now > record.reveal_deadline.get() {
+            return Err(VerifierError::RevealWindowClosed(RevealWindowClosed {}));
+        }
+
+        let mut votes = self.votes.setter(match_id);
+        let mut vote = votes.setter(wallet);
+        if vote.commitment.get() == B256::ZERO {
+            return Err(VerifierError::NoCommitment(NoCommitment {}));
+        }
+        if vote.revealed.get() {
+            return Err(VerifierError::AlreadyRevealed(AlreadyRevealed {}));
+        }
+        if vote_commitment(match_id, is_bot, salt, wallet) != vote.commitment.get() {
+            return Err(VerifierError::CommitmentMismatch(CommitmentMismatch {}));
+        }
+        vote.revealed.set(true);
+        vote.is_bot.set(is_bot);
+
+        evm::log(VoteRevealed {
+            wallet,
+            matchId: match_id,
+            isBot: is_bot,
+        });
+        Ok(())
+    }
+
+    /// Checks a revealed answer against the match's seal once the vote reveal
+    /// window has closed. Rejects any answer that does not match the seal.
+    pub fn reveal_truth(
+        &mut self,
+        match_id: B256,
+        opponent_is_bot: bool,
+        salt: B256,
+        now: U256,
+    ) -> Result<(), VerifierError> {
+        let mut record = self.matches.setter(match_id);
+        if record.opened_at.get() == U256::ZERO {
+            return Err(VerifierError::UnknownMatch(UnknownMatch {
+                matchId: match_id,
+            }));
+        }
+        if now <= record.reveal_deadline.get() {
+            return Err(VerifierError::RevealWindowOpen(RevealWindowOpen {}));
+        }
+        if record.truth_revealed.get() {
+            return Err(VerifierError::MatchAlreadySettled(MatchAlreadySettled {
+                matchId: match_id,
+            }));
+        }
+        if truth_commitment(match_id, opponent_is_bot, salt) != record.truth_commitment.get() {
+            return Err(VerifierError::TruthMismatch(TruthMismatch {
+                matchId: match_id,
+            }));
+        }
+        record.truth_revealed.set(true);
+        Ok(())
+    }
+
+    /// Grades one player's vote against the match's final `answer` into
+    /// their tally.
+    pub fn tally(
+        &mut self,
+        match_id: B256,
+        wallet: Address,
+        answer: bool,
+    ) -> Result<(), VerifierError> {
+        let opened_at = self.matches.getter(match_id).opened_at.get();
+
+        let mut votes = self.votes.setter(match_id);
+        let mut vote = votes.setter(wallet);
+        if vote.commitment.get() == B256::ZERO {
+            return Err(VerifierError::NoCommitment(NoCommitment {}));
+        }
+        if vote.tallied.get() {
+            return Err(VerifierError::AlreadyTallied(AlreadyTallied {}));
+        }
+        vote.tallied.set(true);
+
+        let forfeit = !vote.revealed.get();
+        let correct = !forfeit && vote.is_bot.get() == answer;
+        let latency_ms = (vote.committed_at.get() - opened_at) * U256::from(1_000);
+
+        let mut tally = self.tallies.setter(wallet);
+        let total = tally.total.get();
+        tally.total.set(total + U256::from(1));
+        if correct {
+            let c = tally.correct.get();
+            tally.correct.set(c + U256::from(1));
+        }
+        if forfeit {
+            let f = tally.forfeits.get();
+            tally.forfeits.set(f + U256::from(1));
+        }
+        let sum = tally.latency_sum_ms.get();
+        tally.latency_sum_ms.set(sum.saturating_add(latency_ms));
+        let untallied = tally.untallied.get();
+        tally.untallied.set(untallied - U256::from(1));
+
+        evm::log(VoteTallied {
+            wallet,
+            matchId: match_id,
+            correct,
+            forfeit,
+        });
+        Ok(())
+    }
+
+    /// Drops one player's vote in a voided match without grading it, so it
+    /// no longer holds up their record.
+    pub fn discard(&mut self, match_id: B256, wallet: Address) -> Result<(), VerifierError> {
+        let mut votes = self.votes.setter(match_id);
+        let mut vote = votes.setter(wallet);
+        if vote.commitment.get() == B256::ZERO {
+            return Err(VerifierError::NoCommitment(NoCommitment {}));
+        }
+        if vote.tallied.get() {
+            return Err(VerifierError::AlreadyTallied(AlreadyTallied {}));
+        }
+        vote.tallied.set(true);
+
+        let mut tally = self.tallies.setter(wallet);
+        let untallied = tally.untallied.get();
+        tally.untallied.set(untallied - U256::from(1));
+
+        evm::log(VoteDiscarded {
+            wallet,
+            matchId: match_id,
+        });
+        Ok(())
+    }
+
+    /// Commitments by `wallet` that have not been tallied yet.
+    pub fn untallied_of(&self, wallet: Address) -> U256 {
+        self.tallies.getter(wallet).untallied.get()
+    }
+
+    /// Returns `(correct, total, forfeits, avgLatencyMs)`. Latency has
+    /// one-second resolution; see the module docs.
+    pub fn record_of(&self, wallet: Address) -> (U256, U256, U256, U256) {
+        let tally = self.tallies.getter(wallet);
+        let total = tally.total.get();
+        let avg = if total == U256::ZERO {
+            U256::ZERO
+        } else {
+            tally.latency_sum_ms.get() / total
+        };
+        (tally.correct.get(), total, tally.forfeits.get(), avg)
+    }
+
+    /// Returns `(commitment, revealed, isBot)` for a vote.
+    pub fn vote_of(&self, match_id: B256, wallet: Address) -> (B256, bool, bool) {
+        let votes = self.votes.getter(match_id);
+        let vote = votes.getter(wallet);
+        (
+            vote.commitment.get(),
+            vote.revealed.get(),
+            vote.is_bot.get(),
+        )
+    }
+
+    /// Returns `(openedAt, truthCommitment, commitDeadline, revealDeadline,
+    /// truthRevealed)`.
+    pub fn match_of(&self, match_id: B256) -> (U256, B256, U256, U256, bool) {
+        let record = self.matches.getter(match_id);
+        (
+            record.opened_at.get(),
+            record.truth_commitment.get(),
+            record.commit_deadline.get(),
+            record.reveal_deadline.get(),
+            record.truth_revealed.get(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+    use super::*;
+
+    const ALICE: Address = address!("A11CEacF9aa32246d767FCCD72e02d6bCbcC375d");
+    const MATCH: B256 = B256::repeat_byte(0x11);
+    const SALT: B256 = B256::repeat_byte(0x22);
+
+    fn open(contract: &mut CommitRevealVoting) {
+        let seal = truth_commitment(MATCH, true, SALT);
+        assert!(contract
+            .open_match(
+                MATCH,
+                seal,
+                U256::from(110),
+                U256::from(120),
+                U256::from(100)
+            )
+            .is_ok());
+    }
+
+    #[motsu::test]
+    fn counts_a_commit_until_it_is_tallied(contract: CommitRevealVoting) {
+        open(contract);
+        let commitment = vote_commitment(MATCH, true, SALT, ALICE);
+        assert!(contract
+            .commit(MATCH, ALICE, commitment, U256::from(103))
+            .is_ok());
+        assert_eq!(contract.untallied_of(ALICE), U256::from(1));
+
+        assert!(contract
+            .reveal(MATCH, ALICE, true, SALT, U256::from(115))
+            .is_ok());
+        assert!(contract.tally(MATCH, ALICE, true).is_ok());
+        assert_eq!(contract.untallied_of(ALICE), U256::ZERO);
+        assert_eq!(
+            contract.record_of(ALICE),
+            (U256::from(1), U256::from(1), U256::ZERO, U256::from(3_000))
+        );
+    }
+
+    #[motsu::test]
+    fn discards_a_vote_without_grading_it(contract: CommitRevealVoting) {
+        open(contract);
+        assert!(matches!(
+            contract.discard(MATCH, ALICE),
+            Err(VerifierError::NoCommitment(_))
+        ));
+        let commitment = vote_commitment(MATCH, true, SALT, ALICE);
+        assert!(contract
+            .commit(MATCH, ALICE, commitment, U256::from(103))
+            .is_ok());
+
+        assert!(contract.discard(MATCH, ALICE).is_ok());
+        assert_eq!(contract.untallied_of(ALICE), U256::ZERO);
+        assert_eq!(contract.record_of(ALICE).1, U256::ZERO);
+        assert!(matches!(
+            contract.discard(MATCH, ALICE),
+            Err(VerifierError::AlreadyTallied(_))
+        ));
+        assert!(matches!(
+            contract.tally(MATCH, ALICE, true),
+            Err(VerifierError::AlreadyTallied(_))
+        ));
+    }
+
+    #[motsu::test]
+    fn rejects_a_zero_commitment(contract: CommitRevealVoting) {
+        open(contract);
+        assert!(matches!(
+            contract.commit(MATCH, ALICE, B256::ZERO, U256::from(103)),
+            Err(VerifierError::InvalidCommitment(_))
+        ));
+        assert_eq!(contract.untallied_of(ALICE), U256::ZERO);
+        assert_eq!(contract.vote_of(MATCH, ALICE).0, B256::ZERO);
+    }
+}